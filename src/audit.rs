@@ -0,0 +1,512 @@
+use std::fmt::{Display, Formatter};
+
+use indexmap::IndexMap;
+use orion_error::{ToStructError, UvsFrom};
+
+use crate::{
+    error::{OrionSecReason, SecResult},
+    load::SEC_PREFIX,
+    query::{PathSeg, SecPath},
+    sec::{NoSecConv, SecList, SecObj, SecValueObj, SecValueType},
+    visit::{SecVisitor, visit},
+};
+
+/// 常见的默认/占位口令，大小写不敏感的完全匹配。
+pub const DEFAULT_VALUES: &[&str] = &[
+    "admin",
+    "administrator",
+    "changeme",
+    "changeit",
+    "default",
+    "password",
+    "passw0rd",
+    "pass",
+    "secret",
+    "root",
+    "toor",
+    "test",
+    "guest",
+    "example",
+    "welcome",
+    "letmein",
+    "qwerty",
+    "123456",
+    "12345678",
+    "111111",
+    "000000",
+    "xxx",
+    "todo",
+    "none",
+    "null",
+];
+
+/// 内置的常见口令词，归一化后完全匹配，长度不小于 6 的词也按子串匹配。
+pub const DICTIONARY_WORDS: &[&str] = &[
+    "password",
+    "welcome",
+    "monkey",
+    "dragon",
+    "master",
+    "sunshine",
+    "princess",
+    "football",
+    "baseball",
+    "shadow",
+    "superman",
+    "batman",
+    "iloveyou",
+    "trustno",
+    "qwerty",
+    "qwertyuiop",
+    "asdfgh",
+    "zxcvbn",
+    "abc",
+    "letmein",
+    "login",
+    "admin",
+    "secret",
+    "hello",
+    "freedom",
+    "whatever",
+    "starwars",
+    "computer",
+    "summer",
+    "winter",
+    "spring",
+    "autumn",
+    "access",
+    "flower",
+    "cheese",
+    "pepper",
+    "ninja",
+    "mustang",
+    "michael",
+    "jordan",
+    "hunter",
+    "killer",
+    "soccer",
+    "charlie",
+    "donald",
+    "azerty",
+    "service",
+    "database",
+    "server",
+    "company",
+];
+
+/// 审计发现的问题，只描述特征，不包含取值。
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditIssue {
+    TooShort {
+        len: usize,
+        min: usize,
+    },
+    LowEntropy {
+        bits: f64,
+        min: f64,
+    },
+    DictionaryWord,
+    DefaultValue,
+    /// 与其他路径取值相同。
+    Reused {
+        others: Vec<SecPath>,
+    },
+    /// 取值与键名相同（忽略大小写、分隔符与 `SEC_` 前缀）。
+    EqualsKey,
+}
+
+impl Display for AuditIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditIssue::TooShort { len, min } => write!(f, "too short ({len} < {min})"),
+            AuditIssue::LowEntropy { bits, min } => {
+                write!(f, "low entropy ({bits:.0} bits < {min:.0})")
+            }
+            AuditIssue::DictionaryWord => write!(f, "dictionary word"),
+            AuditIssue::DefaultValue => write!(f, "default value"),
+            AuditIssue::Reused { others } => {
+                let others = others
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "reused by {others}")
+            }
+            AuditIssue::EqualsKey => write!(f, "equals key name"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditFinding {
+    pub path: SecPath,
+    pub issue: AuditIssue,
+}
+
+impl Display for AuditFinding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.issue)
+    }
+}
+
+/// 密文卫生审计：检查密文标量叶子（含密文容器内的叶子，数字等按文本形式）的长度、熵、
+/// 字典词、默认值、跨键复用以及与键名相同的情况。
+///
+/// 熵取字符集估计与按字符频率的香农估计中的较小值，重复字符会降低得分。
+#[derive(Debug, Clone)]
+pub struct SecAuditor {
+    min_length: usize,
+    min_entropy: f64,
+    defaults: Vec<String>,
+    dictionary: Vec<String>,
+}
+
+impl Default for SecAuditor {
+    fn default() -> Self {
+        Self {
+            min_length: 12,
+            min_entropy: 36.0,
+            defaults: DEFAULT_VALUES.iter().map(|s| s.to_string()).collect(),
+            dictionary: DICTIONARY_WORDS.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl SecAuditor {
+    /// 最短长度，0 表示不检查。
+    #[must_use]
+    pub fn with_min_length(mut self, min: usize) -> Self {
+        self.min_length = min;
+        self
+    }
+
+    /// 最低熵（比特），0 表示不检查。
+    #[must_use]
+    pub fn with_min_entropy(mut self, bits: f64) -> Self {
+        self.min_entropy = bits;
+        self
+    }
+
+    /// 追加默认值，例如项目模板里的占位口令。
+    #[must_use]
+    pub fn with_default_values<I, S>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.defaults
+            .extend(values.into_iter().map(|v| v.into().to_lowercase()));
+        self
+    }
+
+    /// 追加字典词，例如公司名、产品名。
+    #[must_use]
+    pub fn with_dictionary_words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.dictionary
+            .extend(words.into_iter().map(|w| normalize_word(&w.into())));
+        self
+    }
+
+    /// 按遍历顺序返回全部问题。
+    pub fn audit(&self, obj: &SecValueObj) -> Vec<AuditFinding> {
+        let mut collect = SecretScalars::default();
+        visit(obj, &mut collect);
+
+        let mut by_value: IndexMap<&str, Vec<&SecPath>> = IndexMap::new();
+        for (path, value) in &collect.found {
+            if !value.is_empty() {
+                by_value.entry(value.as_str()).or_default().push(path);
+            }
+        }
+
+        let mut findings = Vec::new();
+        for (path, value) in &collect.found {
+            let mut push = |issue| {
+                findings.push(AuditFinding {
+                    path: path.clone(),
+                    issue,
+                })
+            };
+            let len = value.chars().count();
+            if len < self.min_length {
+                push(AuditIssue::TooShort {
+                    len,
+                    min: self.min_length,
+                });
+            }
+            let bits = entropy_bits(value);
+            if self.min_entropy > 0.0 && bits < self.min_entropy {
+                push(AuditIssue::LowEntropy {
+                    bits,
+                    min: self.min_entropy,
+                });
+            }
+            if self.defaults.contains(&value.to_lowercase()) {
+                push(AuditIssue::DefaultValue);
+            } else if self.is_dictionary(value) {
+                push(AuditIssue::DictionaryWord);
+            }
+            if let Some(same) = by_value.get(value.as_str())
+                && same.len() > 1
+            {
+                push(AuditIssue::Reused {
+                    others: same
+                        .iter()
+                        .filter(|p| **p != path)
+                        .map(|p| (*p).clone())
+                        .collect(),
+                });
+            }
+            if equals_key(path, value) {
+                push(AuditIssue::EqualsKey);
+            }
+        }
+        findings
+    }
+
+    /// 启动闸门：存在任何问题时返回错误，错误中只列出路径与问题类型。
+    pub fn check(&self, obj: &SecValueObj) -> SecResult<()> {
+        let findings = self.audit(obj);
+        if findings.is_empty() {
+            return Ok(());
+        }
+        let detail = findings
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        OrionSecReason::from_validation()
+            .to_err()
+            .with_detail(format!("weak secrets: {detail}"))
+            .err()
+    }
+
+    fn is_dictionary(&self, value: &str) -> bool {
+        let word = normalize_word(value);
+        !word.is_empty()
+            && self
+                .dictionary
+                .iter()
+                .any(|d| *d == word || (d.len() >= 6 && word.contains(d.as_str())))
+    }
+}
+
+/// 估算熵（比特）：`min(长度 × log2(字符集大小), 长度 × 字符频率香农熵)`。
+pub fn entropy_bits(value: &str) -> f64 {
+    let len = value.chars().count();
+    if len == 0 {
+        return 0.0;
+    }
+    let mut pool = 0u32;
+    if value.chars().any(|c| c.is_ascii_lowercase()) {
+        pool += 26;
+    }
+    if value.chars().any(|c| c.is_ascii_uppercase()) {
+        pool += 26;
+    }
+    if value.chars().any(|c| c.is_ascii_digit()) {
+        pool += 10;
+    }
+    if value.chars().any(|c| c.is_ascii_punctuation() || c == ' ') {
+        pool += 33;
+    }
+    if !value.is_ascii() {
+        pool += 100;
+    }
+    let charset = len as f64 * f64::from(pool.max(1)).log2();
+
+    let mut counts: IndexMap<char, usize> = IndexMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let shannon: f64 = counts
+        .values()
+        .map(|n| {
+            let p = *n as f64 / len as f64;
+            -p * p.log2()
+        })
+        .sum();
+    charset.min(shannon * len as f64)
+}
+
+/// 小写、去掉首尾的数字与符号（`password123!`），再还原中间常见的替换字符（`p@ssw0rd`）。
+fn normalize_word(value: &str) -> String {
+    value
+        .to_lowercase()
+        .trim_matches(|c: char| !c.is_alphabetic())
+        .chars()
+        .map(|c| match c {
+            '0' => 'o',
+            '1' | '!' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' => 't',
+            other => other,
+        })
+        .collect()
+}
+
+fn equals_key(path: &SecPath, value: &str) -> bool {
+    let Some(PathSeg::Key(key)) = path.segments().last() else {
+        return false;
+    };
+    let squash = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    let value = squash(value);
+    let key = key.as_str();
+    !value.is_empty()
+        && (value == squash(key)
+            || key
+                .strip_prefix(SEC_PREFIX)
+                .is_some_and(|rest| value == squash(rest)))
+}
+
+/// 收集密文标量叶子（含密文容器下的叶子）的文本形式，数字口令等同样参与检查。
+#[derive(Default)]
+pub(crate) struct SecretScalars {
+    secret_depth: usize,
+    pub(crate) found: Vec<(SecPath, String)>,
+}
+
+impl SecVisitor for SecretScalars {
+    fn visit_leaf(&mut self, path: &SecPath, value: &SecValueType) {
+        if !matches!(value, SecValueType::Obj(_) | SecValueType::List(_))
+            && (value.is_secret() || self.secret_depth > 0)
+        {
+            self.found
+                .push((path.clone(), value.clone().no_sec().to_string()));
+        }
+    }
+    fn enter_obj(&mut self, _path: &SecPath, obj: &SecObj) -> bool {
+        if obj.is_secret() {
+            self.secret_depth += 1;
+        }
+        true
+    }
+    fn leave_obj(&mut self, _path: &SecPath, obj: &SecObj) {
+        if obj.is_secret() {
+            self.secret_depth -= 1;
+        }
+    }
+    fn enter_list(&mut self, _path: &SecPath, list: &SecList) -> bool {
+        if list.is_secret() {
+            self.secret_depth += 1;
+        }
+        true
+    }
+    fn leave_list(&mut self, _path: &SecPath, list: &SecList) {
+        if list.is_secret() {
+            self.secret_depth -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::SecFrom;
+
+    fn obj(pairs: &[(&str, &str)]) -> SecValueObj {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).into(), SecValueType::sec_from(v.to_string())))
+            .collect()
+    }
+
+    fn issues(findings: &[AuditFinding], path: &str) -> Vec<String> {
+        findings
+            .iter()
+            .filter(|f| f.path.to_string() == path)
+            .map(|f| f.issue.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_entropy_bits() {
+        assert_eq!(entropy_bits(""), 0.0);
+        assert_eq!(entropy_bits("aaaaaaaaaaaaaaaa"), 0.0);
+        assert!(entropy_bits("correct-horse-battery-staple") > 60.0);
+        assert!(entropy_bits("Xk9#mQ2$vL7@pR4!") > entropy_bits("abcdabcdabcdabcd"));
+    }
+
+    #[test]
+    fn test_audit_flags_weak_secrets() {
+        let mut obj = obj(&[
+            ("SEC_DB_PASS", "changeme"),
+            ("SEC_ADMIN", "P@ssw0rd2024!"),
+            ("SEC_API_TOKEN", "sec_api_token"),
+            ("SEC_A", "Xk9#mQ2$vL7@pR4!zW"),
+            ("SEC_B", "Xk9#mQ2$vL7@pR4!zW"),
+            ("SEC_GOOD", "t7Gq-Vn2e-Lw9z-Hk4c"),
+        ]);
+        obj.insert("PUBLIC".into(), SecValueType::nor_from("admin".to_string()));
+        let findings = SecAuditor::default().audit(&obj);
+
+        assert_eq!(
+            issues(&findings, "SEC_DB_PASS"),
+            vec![
+                "too short (8 < 12)",
+                "low entropy (22 bits < 36)",
+                "default value"
+            ]
+        );
+        assert_eq!(issues(&findings, "SEC_ADMIN"), vec!["dictionary word"]);
+        assert!(issues(&findings, "SEC_API_TOKEN").contains(&"equals key name".to_string()));
+        assert_eq!(issues(&findings, "SEC_A"), vec!["reused by SEC_B"]);
+        assert!(issues(&findings, "SEC_GOOD").is_empty());
+        assert!(issues(&findings, "PUBLIC").is_empty());
+
+        for f in &findings {
+            let text = f.to_string();
+            assert!(!text.contains("changeme") && !text.contains("Xk9#"));
+        }
+    }
+
+    #[test]
+    fn test_audit_numeric_secret_from_yaml() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sec_value.yml");
+        std::fs::write(&path, "password: 123456\n").unwrap();
+        let mut obj = crate::load::load_secfile_by(path, crate::load::SecFileFmt::Yaml).unwrap();
+        assert!(matches!(obj["SEC_PASSWORD"], SecValueType::Number(_)));
+        obj.insert("PORT".into(), SecValueType::nor_from(5432u64));
+        let findings = SecAuditor::default().audit(&obj);
+        let pin = issues(&findings, "SEC_PASSWORD");
+        assert!(pin.contains(&"too short (6 < 12)".to_string()), "{pin:?}");
+        assert!(issues(&findings, "PORT").is_empty());
+        assert!(findings.iter().all(|f| !f.to_string().contains("123456")));
+    }
+
+    #[test]
+    fn test_audit_secret_container_and_gate() {
+        let mut inner = SecValueObj::new();
+        inner.insert("PASS".into(), SecValueType::nor_from("hunter2".to_string()));
+        let mut obj = SecValueObj::new();
+        obj.insert("DB".into(), SecValueType::Obj(SecObj::sec_from(inner)));
+        let auditor = SecAuditor::default().with_dictionary_words(["Galaxy"]);
+        let findings = auditor.audit(&obj);
+        assert!(findings.iter().all(|f| f.path.to_string() == "DB.PASS"));
+        assert!(!findings.is_empty());
+        let err = auditor.check(&obj).unwrap_err().to_string();
+        assert!(err.contains("DB.PASS") && !err.contains("hunter2"));
+
+        let company = self::obj(&[("SEC_X", "galaxy2026release")]);
+        assert_eq!(
+            issues(&auditor.clone().with_min_length(0).audit(&company), "SEC_X"),
+            vec!["dictionary word"]
+        );
+        let relaxed = SecAuditor::default()
+            .with_min_length(0)
+            .with_min_entropy(0.0);
+        assert!(relaxed.check(&self::obj(&[("K", "zq")])).is_ok());
+    }
+}
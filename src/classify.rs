@@ -0,0 +1,301 @@
+use indexmap::IndexMap;
+use orion_error::{ToStructError, UvsFrom};
+use orion_variate::vars::ValueType;
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    error::{OrionSecReason, SecResult},
+    query::{PathSeg, SecPath, replace_with, wildcard_match},
+    sec::{SecConv, SecFrom, SecList, SecObj, SecValueObj, SecValueType},
+    types::UniString,
+    visit::{SecVisitorMut, visit_mut},
+};
+
+/// 内置的敏感键名模式，按键名（不含路径）做大小写不敏感的通配匹配。
+pub const DEFAULT_SECRET_KEYS: &[&str] = &[
+    "*PASSWORD*",
+    "*PASSWD*",
+    "*PASSPHRASE*",
+    "*SECRET*",
+    "*TOKEN*",
+    "*CREDENTIAL*",
+    "*PRIVATE_KEY*",
+    "*APIKEY*",
+    "*API_KEY*",
+    "*_KEY",
+];
+
+/// 判定节点为密文的单条规则。
+#[derive(Debug, Clone)]
+pub enum SecRule {
+    /// 通配匹配节点自身的键名，如 `*password*`。
+    Key(String),
+    /// 通配匹配节点的完整路径，如 `DB.*.PASSWORD`。
+    Path(String),
+    /// 正则匹配节点的完整路径（大小写不敏感）。
+    Regex(Regex),
+}
+
+impl SecRule {
+    pub fn matches(&self, path: &SecPath) -> bool {
+        match self {
+            SecRule::Key(pattern) => match path.segments().last() {
+                Some(PathSeg::Key(k)) => wildcard_match(pattern, k.as_str()),
+                _ => false,
+            },
+            SecRule::Path(pattern) => wildcard_match(pattern, &path.to_string()),
+            SecRule::Regex(re) => re.is_match(&path.to_string()),
+        }
+    }
+}
+
+/// 按键名/路径规则把普通配置转换为 `SecValueType`：
+/// 命中规则的节点（含整个子树）标记为密文，其余保持明文。
+///
+/// 介于 `SecFrom::sec_from`（全部密文）与 `SecFrom::nor_from`（全部明文）之间，
+/// 适用于同时包含敏感与非敏感字段的常规配置文件。
+#[derive(Debug, Clone)]
+pub struct SecClassifier {
+    rules: Vec<SecRule>,
+}
+
+impl Default for SecClassifier {
+    fn default() -> Self {
+        Self {
+            rules: DEFAULT_SECRET_KEYS
+                .iter()
+                .map(|p| SecRule::Key(p.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl SecClassifier {
+    /// 不带任何规则的空分类器。
+    pub fn empty() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    pub fn rules(&self) -> &[SecRule] {
+        &self.rules
+    }
+
+    #[must_use]
+    pub fn with_rule(mut self, rule: SecRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    #[must_use]
+    pub fn with_key<S: Into<String>>(self, pattern: S) -> Self {
+        self.with_rule(SecRule::Key(pattern.into()))
+    }
+
+    #[must_use]
+    pub fn with_path<S: Into<String>>(self, pattern: S) -> Self {
+        self.with_rule(SecRule::Path(pattern.into()))
+    }
+
+    pub fn with_regex(self, pattern: &str) -> SecResult<Self> {
+        let re = RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| {
+                OrionSecReason::from_validation()
+                    .to_err()
+                    .with_detail(format!("invalid secret regex `{pattern}`: {e}"))
+            })?;
+        Ok(self.with_rule(SecRule::Regex(re)))
+    }
+
+    pub fn is_secret_path(&self, path: &SecPath) -> bool {
+        !path.is_empty() && self.rules.iter().any(|r| r.matches(path))
+    }
+
+    pub fn classify(&self, value: ValueType) -> SecValueType {
+        self.convert(&SecPath::new(), value)
+    }
+
+    pub fn classify_obj<K, I>(&self, map: I) -> SecValueObj
+    where
+        K: Into<UniString>,
+        I: IntoIterator<Item = (K, ValueType)>,
+    {
+        let root = SecPath::new();
+        map.into_iter()
+            .map(|(k, v)| {
+                let key: UniString = k.into();
+                let value = self.convert(&root.child(PathSeg::Key(key.clone())), v);
+                (key, value)
+            })
+            .collect()
+    }
+
+    /// 在已有的 `SecValueObj` 上补充标记，只会把命中节点升级为密文，返回命中数量。
+    pub fn apply(&self, obj: &mut SecValueObj) -> usize {
+        let mut marker = Marker {
+            classifier: self,
+            count: 0,
+        };
+        visit_mut(obj, &mut marker);
+        marker.count
+    }
+
+    fn convert(&self, path: &SecPath, value: ValueType) -> SecValueType {
+        if self.is_secret_path(path) {
+            return SecValueType::sec_from(value);
+        }
+        match value {
+            ValueType::Obj(map) => SecValueType::from(
+                map.into_iter()
+                    .map(|(k, v)| {
+                        let key = UniString::from(k);
+                        let value = self.convert(&path.child(PathSeg::Key(key.clone())), v);
+                        (key, value)
+                    })
+                    .collect::<IndexMap<_, _>>(),
+            ),
+            ValueType::List(list) => SecValueType::from(
+                list.into_iter()
+                    .enumerate()
+                    .map(|(i, v)| self.convert(&path.child(PathSeg::Index(i)), v))
+                    .collect::<Vec<_>>(),
+            ),
+            other => SecValueType::nor_from(other),
+        }
+    }
+}
+
+struct Marker<'a> {
+    classifier: &'a SecClassifier,
+    count: usize,
+}
+
+impl SecVisitorMut for Marker<'_> {
+    fn visit_leaf_mut(&mut self, path: &SecPath, value: &mut SecValueType) {
+        if self.classifier.is_secret_path(path) {
+            replace_with(value, SecValueType::to_sec);
+            self.count += 1;
+        }
+    }
+
+    fn enter_obj_mut(&mut self, path: &SecPath, obj: &mut SecObj) -> bool {
+        if !self.classifier.is_secret_path(path) {
+            return true;
+        }
+        let children = std::mem::take(obj.value_mut());
+        *obj.value_mut() = children.to_sec();
+        obj.set_secret(true);
+        self.count += 1;
+        false
+    }
+
+    fn enter_list_mut(&mut self, path: &SecPath, list: &mut SecList) -> bool {
+        if !self.classifier.is_secret_path(path) {
+            return true;
+        }
+        let items = std::mem::take(list.value_mut());
+        *list.value_mut() = items.to_sec();
+        list.set_secret(true);
+        self.count += 1;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::ValueGetter;
+
+    fn is_secret(obj: &SecValueObj, path: &str) -> bool {
+        match obj.value_get(path) {
+            Some(SecValueType::String(v)) => v.is_secret(),
+            Some(SecValueType::Number(v)) => v.is_secret(),
+            other => panic!("unexpected {path}: {other:?}"),
+        }
+    }
+
+    fn sample() -> IndexMap<String, ValueType> {
+        let mut db = IndexMap::new();
+        db.insert("host".to_string(), ValueType::from("localhost"));
+        db.insert("password".to_string(), ValueType::from("p@ss"));
+        db.insert("port".to_string(), ValueType::Number(5432));
+
+        let mut root = IndexMap::new();
+        root.insert("db".to_string(), ValueType::Obj(db));
+        root.insert("github_token".to_string(), ValueType::from("ghp_x"));
+        root.insert("signing_key".to_string(), ValueType::from("k"));
+        root.insert("keyring".to_string(), ValueType::from("login"));
+        root.insert("name".to_string(), ValueType::from("app"));
+        root
+    }
+
+    #[test]
+    fn test_default_rules_by_key_name() {
+        let obj = SecClassifier::default().classify_obj(sample());
+        assert!(is_secret(&obj, "db.password"));
+        assert!(is_secret(&obj, "github_token"));
+        assert!(is_secret(&obj, "signing_key"));
+        assert!(!is_secret(&obj, "keyring"));
+        assert!(!is_secret(&obj, "name"));
+        assert!(!is_secret(&obj, "db.host"));
+        assert!(!is_secret(&obj, "db.port"));
+    }
+
+    #[test]
+    fn test_path_glob_rule() {
+        let obj = SecClassifier::empty()
+            .with_path("db.*")
+            .classify_obj(sample());
+        assert!(is_secret(&obj, "db.host"));
+        assert!(is_secret(&obj, "db.port"));
+        assert!(!is_secret(&obj, "github_token"));
+    }
+
+    #[test]
+    fn test_regex_rule() {
+        let obj = SecClassifier::empty()
+            .with_regex(r"^db\.(host|port)$")
+            .unwrap()
+            .classify_obj(sample());
+        assert!(is_secret(&obj, "db.host"));
+        assert!(!is_secret(&obj, "db.password"));
+        assert!(SecClassifier::empty().with_regex("(").is_err());
+    }
+
+    #[test]
+    fn test_secret_container_marks_subtree() {
+        let mut creds = IndexMap::new();
+        creds.insert("user".to_string(), ValueType::from("u"));
+        creds.insert(
+            "list".to_string(),
+            ValueType::List(vec![ValueType::from("a")]),
+        );
+        let mut root = IndexMap::new();
+        root.insert("credentials".to_string(), ValueType::Obj(creds));
+
+        let obj = SecClassifier::default().classify_obj(root);
+        assert!(is_secret(&obj, "credentials.user"));
+        assert!(is_secret(&obj, "credentials.list[0]"));
+    }
+
+    #[test]
+    fn test_apply_on_existing_obj() {
+        let mut obj = SecClassifier::empty().classify_obj(sample());
+        assert!(!is_secret(&obj, "db.password"));
+        let hits = SecClassifier::default().apply(&mut obj);
+        assert_eq!(hits, 3);
+        assert!(is_secret(&obj, "db.password"));
+        assert!(!is_secret(&obj, "db.host"));
+    }
+
+    #[test]
+    fn test_classify_value_type() {
+        let value = SecClassifier::default().classify(ValueType::Obj(sample()));
+        let SecValueType::Obj(obj) = value else {
+            panic!("expect obj");
+        };
+        assert!(is_secret(obj.value(), "github_token"));
+        assert!(!is_secret(obj.value(), "name"));
+    }
+}
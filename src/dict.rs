@@ -0,0 +1,181 @@
+use indexmap::IndexSet;
+use orion_variate::vars::{EnvDict, ValueType};
+
+use crate::{
+    error::SecResult,
+    load::{SecFileFmt, dot_path, load_secfile, load_secfile_by},
+    sec::{NoSecConv, SECRET_MASK, SecValueObj, SecValueType},
+    types::UniString,
+};
+
+/// 带密级信息的 `EnvDict`。
+///
+/// 取值已转换为明文 `ValueType`，可直接交给 orion-variate 渲染模板；
+/// `secret_keys` 记录含有密文的顶层键，供日志与输出脱敏使用。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SecEnvDict {
+    dict: EnvDict,
+    secret_keys: IndexSet<UniString>,
+}
+
+impl SecEnvDict {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dict(&self) -> &EnvDict {
+        &self.dict
+    }
+
+    pub fn secret_keys(&self) -> &IndexSet<UniString> {
+        &self.secret_keys
+    }
+
+    pub fn is_secret<K: Into<UniString>>(&self, key: K) -> bool {
+        self.secret_keys.contains(&key.into())
+    }
+
+    /// 插入一项，`is_secret` 为真时同时登记为密文键；覆盖已有键时更新其密级。
+    pub fn insert<K: Into<UniString>>(&mut self, key: K, value: ValueType, is_secret: bool) {
+        let key = key.into();
+        if is_secret {
+            self.secret_keys.insert(key.clone());
+        } else {
+            self.secret_keys.shift_remove(&key);
+        }
+        self.dict.insert(key, value);
+    }
+
+    /// 返回密文键被替换为掩码的副本，适合直接打印或写入日志。
+    pub fn masked(&self) -> EnvDict {
+        let mut dict = EnvDict::new();
+        for (k, v) in self.dict.iter() {
+            let value = if self.secret_keys.contains(k) {
+                ValueType::String(SECRET_MASK.to_string())
+            } else {
+                v.clone()
+            };
+            dict.insert(k.clone(), value);
+        }
+        dict
+    }
+
+    pub fn into_parts(self) -> (EnvDict, IndexSet<UniString>) {
+        (self.dict, self.secret_keys)
+    }
+}
+
+impl From<SecValueObj> for SecEnvDict {
+    fn from(obj: SecValueObj) -> Self {
+        let mut dict = Self::new();
+        for (k, v) in obj {
+            let is_secret = v.contains_secret();
+            dict.insert(k, v.no_sec(), is_secret);
+        }
+        dict
+    }
+}
+
+impl From<SecEnvDict> for EnvDict {
+    fn from(value: SecEnvDict) -> Self {
+        value.dict
+    }
+}
+
+impl FromIterator<(UniString, SecValueType)> for SecEnvDict {
+    fn from_iter<T: IntoIterator<Item = (UniString, SecValueType)>>(iter: T) -> Self {
+        Self::from(iter.into_iter().collect::<SecValueObj>())
+    }
+}
+
+/// 与 `load_sec_dict` 相同，但保留每个键的密级。
+pub fn load_sec_env_dict() -> SecResult<SecEnvDict> {
+    Ok(SecEnvDict::from(load_secfile()?))
+}
+
+/// 与 `load_sec_dict_by` 相同，但保留每个键的密级。
+pub fn load_sec_env_dict_by(
+    dot_name: &str,
+    file_name: &str,
+    fmt: SecFileFmt,
+) -> SecResult<SecEnvDict> {
+    let sec_file = dot_path(dot_name).join(file_name);
+    Ok(SecEnvDict::from(load_secfile_by(sec_file, fmt)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::SecFrom;
+    use crate::types::UniCaseMap;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn sample() -> SecValueObj {
+        let mut db = UniCaseMap::new();
+        db.insert("host".into(), SecValueType::nor_from("pg".to_string()));
+        db.insert("pass".into(), SecValueType::sec_from("p".to_string()));
+        let mut root = UniCaseMap::new();
+        root.insert("SEC_DB".into(), SecValueType::from(db));
+        root.insert("SEC_TOKEN".into(), SecValueType::sec_from("t".to_string()));
+        root.insert("APP".into(), SecValueType::nor_from("demo".to_string()));
+        root
+    }
+
+    #[test]
+    fn test_sec_env_dict_tracks_secret_keys() {
+        let dict = SecEnvDict::from(sample());
+        assert_eq!(dict.dict().len(), 3);
+        assert!(dict.is_secret("sec_token"));
+        assert!(dict.is_secret("SEC_DB"));
+        assert!(!dict.is_secret("APP"));
+        assert_eq!(
+            dict.dict().get("SEC_TOKEN"),
+            Some(&ValueType::String("t".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_sec_env_dict_masked() {
+        let masked = SecEnvDict::from(sample()).masked();
+        assert_eq!(
+            masked.get("SEC_TOKEN"),
+            Some(&ValueType::String(SECRET_MASK.to_string()))
+        );
+        assert_eq!(
+            masked.get("APP"),
+            Some(&ValueType::String("demo".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_sec_env_dict_insert_updates_flag() {
+        let mut dict = SecEnvDict::new();
+        dict.insert("KEY", ValueType::Bool(true), true);
+        assert!(dict.is_secret("key"));
+        dict.insert("KEY", ValueType::Bool(false), false);
+        assert!(!dict.is_secret("key"));
+        let (env, secrets) = dict.into_parts();
+        assert_eq!(env.len(), 1);
+        assert!(secrets.is_empty());
+    }
+
+    #[test]
+    fn test_load_sec_env_dict_by_marks_file_values() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut file = NamedTempFile::with_suffix_in(".yml", dir.path()).unwrap();
+        writeln!(file, "db_pass: secret").unwrap();
+        let name = file
+            .path()
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        let dot = dir.path().to_str().unwrap();
+
+        let dict = load_sec_env_dict_by(dot, &name, SecFileFmt::Yaml).unwrap();
+        assert_eq!(dict.secret_keys().len(), 1);
+        assert!(dict.is_secret("SEC_DB_PASS"));
+    }
+}
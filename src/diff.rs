@@ -0,0 +1,519 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    sync::OnceLock,
+};
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, digest::Update};
+
+use crate::{
+    error::SecResult,
+    query::{PathSeg, SecPath},
+    random,
+    sec::{SecValueObj, SecValueType},
+};
+
+const FINGERPRINT_LEN: usize = 12;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 值指纹：类型、密级与摘要前缀，不包含任何明文。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingerprint {
+    pub is_secret: bool,
+    pub kind: &'static str,
+    /// 摘要算法：`hmac-sha256` 或显式选择的无密钥 `sha256`。
+    pub scheme: &'static str,
+    pub digest: String,
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_secret {
+            write!(f, "secret ")?;
+        }
+        write!(f, "{} {}:{}", self.kind, self.scheme, self.digest)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+    /// 取值不变，仅密级发生变化。
+    Reclassified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecChange {
+    pub path: SecPath,
+    pub kind: ChangeKind,
+    pub before: Option<Fingerprint>,
+    pub after: Option<Fingerprint>,
+}
+
+impl Display for SecChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.kind, &self.before, &self.after) {
+            (ChangeKind::Added, _, Some(after)) => write!(f, "+ {} ({after})", self.path),
+            (ChangeKind::Removed, Some(before), _) => write!(f, "- {} ({before})", self.path),
+            (ChangeKind::Reclassified, Some(before), Some(after)) => write!(
+                f,
+                "! {} ({} -> {})",
+                self.path,
+                secrecy(before.is_secret),
+                secrecy(after.is_secret)
+            ),
+            (_, Some(before), Some(after)) => {
+                write!(f, "~ {} ({before} -> {after})", self.path)
+            }
+            _ => write!(f, "? {}", self.path),
+        }
+    }
+}
+
+fn secrecy(is_secret: bool) -> &'static str {
+    if is_secret { "secret" } else { "public" }
+}
+
+/// 两个 `SecValueObj` 快照之间的路径级差异。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SecDiff {
+    changes: Vec<SecChange>,
+}
+
+impl SecDiff {
+    pub fn changes(&self) -> &[SecChange] {
+        &self.changes
+    }
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+    pub fn of_kind(&self, kind: ChangeKind) -> impl Iterator<Item = &SecChange> {
+        self.changes.iter().filter(move |c| c.kind == kind)
+    }
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} removed, {} modified, {} reclassified",
+            self.of_kind(ChangeKind::Added).count(),
+            self.of_kind(ChangeKind::Removed).count(),
+            self.of_kind(ChangeKind::Modified).count(),
+            self.of_kind(ChangeKind::Reclassified).count()
+        )
+    }
+}
+
+impl Display for SecDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// 计算差异与指纹。
+///
+/// 指纹默认为 `HMAC-SHA256(进程内随机密钥, 值)` 的前缀，只在同一进程内可比较；
+/// 需跨进程比较（如审计日志）时用 [`SecDiffer::keyed`] 指定私有密钥。
+/// 无密钥的 SHA-256 可被离线穷举低熵口令，只能通过 [`SecDiffer::unkeyed`] 显式选择。
+#[derive(Clone)]
+pub struct SecDiffer {
+    mac: Option<HmacSha256>,
+}
+
+impl Debug for SecDiffer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecDiffer")
+            .field("keyed", &self.mac.is_some())
+            .finish()
+    }
+}
+
+impl SecDiffer {
+    /// 使用进程内随机密钥；首次调用时从操作系统 CSPRNG 取密钥，失败时返回错误。
+    pub fn new() -> SecResult<Self> {
+        Ok(Self {
+            mac: Some(hmac(process_key()?)),
+        })
+    }
+
+    /// 以调用方提供的密钥计算 HMAC，相同密钥下指纹跨进程稳定。
+    pub fn keyed<K: AsRef<[u8]>>(key: K) -> Self {
+        Self {
+            mac: Some(hmac(key.as_ref())),
+        }
+    }
+
+    /// 改用调用方提供的密钥，见 [`SecDiffer::keyed`]。
+    #[must_use]
+    pub fn with_key<K: AsRef<[u8]>>(mut self, key: K) -> Self {
+        self.mac = Some(hmac(key.as_ref()));
+        self
+    }
+
+    /// 不使用密钥，指纹为 `SHA-256(值)` 的前缀，任何人都可复算。
+    pub fn unkeyed() -> Self {
+        Self { mac: None }
+    }
+
+    pub fn fingerprint(&self, value: &SecValueType) -> Fingerprint {
+        let (scheme, digest): (_, [u8; 32]) = match &self.mac {
+            Some(mac) => {
+                let mut mac = mac.clone();
+                feed(&mut mac, value);
+                ("hmac-sha256", mac.finalize().into_bytes().into())
+            }
+            None => {
+                let mut hasher = Sha256::new();
+                feed(&mut hasher, value);
+                ("sha256", hasher.finalize().into())
+            }
+        };
+        let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+        Fingerprint {
+            is_secret: value.is_secret(),
+            kind: kind_name(value),
+            scheme,
+            digest: hex[..FINGERPRINT_LEN].to_string(),
+        }
+    }
+
+    pub fn diff(&self, before: &SecValueObj, after: &SecValueObj) -> SecDiff {
+        let mut changes = Vec::new();
+        self.diff_obj(&SecPath::new(), before, after, &mut changes);
+        SecDiff { changes }
+    }
+
+    fn diff_obj(
+        &self,
+        path: &SecPath,
+        before: &SecValueObj,
+        after: &SecValueObj,
+        out: &mut Vec<SecChange>,
+    ) {
+        for (k, old) in before.iter() {
+            let child = path.child(PathSeg::Key(k.clone()));
+            match after.get(k) {
+                Some(new) => self.diff_node(&child, old, new, out),
+                None => out.push(self.removed(child, old)),
+            }
+        }
+        for (k, new) in after.iter() {
+            if !before.contains_key(k) {
+                out.push(self.added(path.child(PathSeg::Key(k.clone())), new));
+            }
+        }
+    }
+
+    fn diff_node(
+        &self,
+        path: &SecPath,
+        before: &SecValueType,
+        after: &SecValueType,
+        out: &mut Vec<SecChange>,
+    ) {
+        match (before, after) {
+            (SecValueType::Obj(old), SecValueType::Obj(new)) => {
+                if old.is_secret() != new.is_secret() {
+                    out.push(self.changed(ChangeKind::Reclassified, path, before, after));
+                }
+                self.diff_obj(path, old.value(), new.value(), out);
+            }
+            (SecValueType::List(old), SecValueType::List(new)) => {
+                if old.is_secret() != new.is_secret() {
+                    out.push(self.changed(ChangeKind::Reclassified, path, before, after));
+                }
+                let (old, new) = (old.value(), new.value());
+                for i in 0..old.len().max(new.len()) {
+                    let child = path.child(PathSeg::Index(i));
+                    match (old.get(i), new.get(i)) {
+                        (Some(o), Some(n)) => self.diff_node(&child, o, n, out),
+                        (Some(o), None) => out.push(self.removed(child, o)),
+                        (None, Some(n)) => out.push(self.added(child, n)),
+                        (None, None) => {}
+                    }
+                }
+            }
+            _ => {
+                let (old_fp, new_fp) = (self.fingerprint(before), self.fingerprint(after));
+                if old_fp.kind != new_fp.kind || old_fp.digest != new_fp.digest {
+                    out.push(SecChange {
+                        path: path.clone(),
+                        kind: ChangeKind::Modified,
+                        before: Some(old_fp),
+                        after: Some(new_fp),
+                    });
+                } else if old_fp.is_secret != new_fp.is_secret {
+                    out.push(SecChange {
+                        path: path.clone(),
+                        kind: ChangeKind::Reclassified,
+                        before: Some(old_fp),
+                        after: Some(new_fp),
+                    });
+                }
+            }
+        }
+    }
+
+    fn added(&self, path: SecPath, value: &SecValueType) -> SecChange {
+        SecChange {
+            path,
+            kind: ChangeKind::Added,
+            before: None,
+            after: Some(self.fingerprint(value)),
+        }
+    }
+
+    fn removed(&self, path: SecPath, value: &SecValueType) -> SecChange {
+        SecChange {
+            path,
+            kind: ChangeKind::Removed,
+            before: Some(self.fingerprint(value)),
+            after: None,
+        }
+    }
+
+    fn changed(
+        &self,
+        kind: ChangeKind,
+        path: &SecPath,
+        before: &SecValueType,
+        after: &SecValueType,
+    ) -> SecChange {
+        SecChange {
+            path: path.clone(),
+            kind,
+            before: Some(self.fingerprint(before)),
+            after: Some(self.fingerprint(after)),
+        }
+    }
+}
+
+/// 使用默认的进程内随机密钥比较两个快照；取不到随机密钥时返回错误。
+pub fn diff_sec_obj(before: &SecValueObj, after: &SecValueObj) -> SecResult<SecDiff> {
+    Ok(SecDiffer::new()?.diff(before, after))
+}
+
+fn process_key() -> SecResult<&'static [u8; 32]> {
+    static KEY: OnceLock<[u8; 32]> = OnceLock::new();
+    if let Some(key) = KEY.get() {
+        return Ok(key);
+    }
+    let mut key = [0u8; 32];
+    random::fill(&mut key)?;
+    Ok(KEY.get_or_init(|| key))
+}
+
+fn hmac(key: &[u8]) -> HmacSha256 {
+    HmacSha256::new_from_slice(key).expect("hmac accepts keys of any length")
+}
+
+fn kind_name(value: &SecValueType) -> &'static str {
+    match value {
+        SecValueType::String(_) => "string",
+        SecValueType::Bool(_) => "bool",
+        SecValueType::Number(_) => "number",
+        SecValueType::Float(_) => "float",
+        SecValueType::Ip(_) => "ip",
+        SecValueType::Obj(_) => "obj",
+        SecValueType::List(_) => "list",
+    }
+}
+
+/// 按类型标签 + 长度前缀写入摘要，密级不参与计算，保证同值不同密级的指纹一致。
+fn feed<H: Update>(hasher: &mut H, value: &SecValueType) {
+    fn bytes<H: Update>(hasher: &mut H, tag: u8, data: &[u8]) {
+        hasher.update(&[tag]);
+        hasher.update(&(data.len() as u64).to_be_bytes());
+        hasher.update(data);
+    }
+    match value {
+        SecValueType::String(v) => bytes(hasher, b's', v.value().as_bytes()),
+        SecValueType::Bool(v) => bytes(hasher, b'b', &[u8::from(*v.value())]),
+        SecValueType::Number(v) => bytes(hasher, b'n', &v.value().to_be_bytes()),
+        SecValueType::Float(v) => bytes(hasher, b'f', &v.value().to_bits().to_be_bytes()),
+        SecValueType::Ip(v) => bytes(hasher, b'i', v.value().to_string().as_bytes()),
+        SecValueType::Obj(v) => {
+            bytes(hasher, b'o', &(v.value().len() as u64).to_be_bytes());
+            for (k, item) in v.value() {
+                bytes(hasher, b'k', k.as_str().as_bytes());
+                feed(hasher, item);
+            }
+        }
+        SecValueType::List(v) => {
+            bytes(hasher, b'l', &(v.value().len() as u64).to_be_bytes());
+            for item in v.value() {
+                feed(hasher, item);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::SecFrom;
+    use crate::types::UniCaseMap;
+
+    fn snapshot(pass: &str, extra: Option<(&str, &str)>) -> SecValueObj {
+        let mut db = UniCaseMap::new();
+        db.insert("user".into(), SecValueType::nor_from("root".to_string()));
+        db.insert("pass".into(), SecValueType::sec_from(pass.to_string()));
+        let mut root = UniCaseMap::new();
+        root.insert("db".into(), SecValueType::from(db));
+        if let Some((k, v)) = extra {
+            root.insert(k.into(), SecValueType::sec_from(v.to_string()));
+        }
+        root
+    }
+
+    #[test]
+    fn test_diff_identical_is_empty() {
+        let a = snapshot("p1", None);
+        let diff = diff_sec_obj(&a, &a.clone()).unwrap();
+        assert!(diff.is_empty());
+        assert_eq!(
+            diff.summary(),
+            "0 added, 0 removed, 0 modified, 0 reclassified"
+        );
+    }
+
+    #[test]
+    fn test_diff_added_removed_modified() {
+        let before = snapshot("p1", Some(("old_token", "t-old")));
+        let after = snapshot("p2", Some(("new_token", "t-new")));
+        let diff = diff_sec_obj(&before, &after).unwrap();
+
+        let paths: Vec<(ChangeKind, String)> = diff
+            .changes()
+            .iter()
+            .map(|c| (c.kind, c.path.to_string()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (ChangeKind::Modified, "DB.PASS".to_string()),
+                (ChangeKind::Removed, "OLD_TOKEN".to_string()),
+                (ChangeKind::Added, "NEW_TOKEN".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_render_never_prints_plaintext() {
+        let before = snapshot("hunter2", None);
+        let after = snapshot("correct-horse", Some(("api_key", "sk-live-123")));
+        let text = diff_sec_obj(&before, &after).unwrap().to_string();
+        for secret in ["hunter2", "correct-horse", "sk-live-123"] {
+            assert!(!text.contains(secret), "leaked {secret} in {text}");
+        }
+        assert!(text.contains("~ DB.PASS (secret string hmac-sha256:"));
+        assert!(text.contains("+ API_KEY (secret string hmac-sha256:"));
+        assert!(text.ends_with("1 added, 0 removed, 1 modified, 0 reclassified"));
+    }
+
+    #[test]
+    fn test_diff_reclassified() {
+        let before = snapshot("p1", None);
+        let mut after = before.clone();
+        let db = after.get_mut(&crate::types::UniString::from("db")).unwrap();
+        *db = db.clone().to_sec();
+        let diff = diff_sec_obj(&before, &after).unwrap();
+        let kinds: Vec<_> = diff.changes().iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![ChangeKind::Reclassified, ChangeKind::Reclassified]
+        );
+        assert_eq!(diff.changes()[1].path.to_string(), "DB.USER");
+        assert_eq!(
+            diff.changes()[1].to_string(),
+            "! DB.USER (public -> secret)"
+        );
+    }
+
+    #[test]
+    fn test_diff_list_and_type_change() {
+        let mut before = UniCaseMap::new();
+        before.insert(
+            "hosts".into(),
+            SecValueType::from(vec![SecValueType::nor_from("a".to_string())]),
+        );
+        before.insert("port".into(), SecValueType::nor_from(80u64));
+        let mut after = UniCaseMap::new();
+        after.insert(
+            "hosts".into(),
+            SecValueType::from(vec![
+                SecValueType::nor_from("a".to_string()),
+                SecValueType::nor_from("b".to_string()),
+            ]),
+        );
+        after.insert("port".into(), SecValueType::nor_from("80".to_string()));
+
+        let diff = diff_sec_obj(&before, &after).unwrap();
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff.changes()[0].kind, ChangeKind::Added);
+        assert_eq!(diff.changes()[0].path.to_string(), "HOSTS[1]");
+        assert_eq!(diff.changes()[1].kind, ChangeKind::Modified);
+        assert_eq!(diff.changes()[1].before.as_ref().unwrap().kind, "number");
+        assert_eq!(diff.changes()[1].after.as_ref().unwrap().kind, "string");
+    }
+
+    #[test]
+    fn test_fingerprint_keyed_by_default() {
+        let value = SecValueType::sec_from("same".to_string());
+        let process = SecDiffer::new().unwrap().fingerprint(&value);
+        let keyed = SecDiffer::keyed("pepper").fingerprint(&value);
+        let unkeyed = SecDiffer::unkeyed().fingerprint(&value);
+        assert_eq!(process.scheme, "hmac-sha256");
+        assert_eq!(unkeyed.scheme, "sha256");
+        assert_ne!(process.digest, keyed.digest);
+        assert_ne!(process.digest, unkeyed.digest);
+        assert_eq!(process.digest.len(), FINGERPRINT_LEN);
+        assert_eq!(process, SecDiffer::new().unwrap().fingerprint(&value));
+        assert_eq!(keyed, SecDiffer::keyed("pepper").fingerprint(&value));
+        assert!(!format!("{:?}", SecDiffer::keyed("pepper")).contains("pepper"));
+        assert_eq!(
+            SecDiffer::unkeyed().with_key("pepper").fingerprint(&value),
+            keyed
+        );
+    }
+
+    #[test]
+    fn test_hmac_rfc4231_vector() {
+        // RFC 4231 测试用例 2
+        let mut mac = hmac(b"Jefe");
+        Mac::update(&mut mac, b"what do ya want for nothing?");
+        let hex: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        assert_eq!(
+            hex,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        // 测试用例 6：长于块大小的密钥
+        let mut mac = hmac(&[0xaa; 131]);
+        Mac::update(
+            &mut mac,
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+        );
+        let hex: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        assert_eq!(
+            hex,
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+}
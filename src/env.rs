@@ -0,0 +1,257 @@
+use std::{env, net::IpAddr};
+
+use log::warn;
+use orion_error::{ToStructError, UvsFrom};
+use orion_variate::vars::ValueType;
+
+use crate::{
+    error::{OrionSecReason, SecResult},
+    load::SEC_PREFIX,
+    sec::{SecFrom, SecValueObj, SecValueType},
+    types::{UniCaseMap, UniString},
+};
+
+/// 环境变量名中表示下一层嵌套的分隔符，如 `SEC_DB__PASSWORD`。
+pub const ENV_NEST_SEP: &str = "__";
+
+/// 从进程环境读取 `SEC_*` 变量，构造全部为密文的 `SecValueObj`。
+pub fn load_sec_env() -> SecResult<SecValueObj> {
+    load_sec_env_by(SEC_PREFIX)
+}
+
+/// 从进程环境读取以 `prefix` 开头的变量（前缀大小写不敏感，空前缀表示全部变量）。
+///
+/// 非 UTF-8 的变量会被忽略并记录警告。
+pub fn load_sec_env_by(prefix: &str) -> SecResult<SecValueObj> {
+    let vars = env::vars_os().filter_map(|(k, v)| match (k.into_string(), v.into_string()) {
+        (Ok(k), Ok(v)) => Some((k, v)),
+        (Ok(k), Err(_)) => {
+            warn!(target: "exec", "  skip non utf-8 env {}", k);
+            None
+        }
+        _ => None,
+    });
+    sec_obj_from_vars(vars, prefix)
+}
+
+/// 将 `(名称, 取值)` 序列按 [`ENV_NEST_SEP`] 展开为嵌套的密文 `SecValueObj`。
+///
+/// 顶层键保留前缀（与 `load_secfile_by` 的 `SEC_` 键一致），取值按
+/// 布尔、整数、浮点、IP、字符串的顺序识别类型。
+/// 同一路径既是取值又是对象（如 `SEC_DB` 与 `SEC_DB__USER`）时返回错误。
+pub fn sec_obj_from_vars<I, K, V>(vars: I, prefix: &str) -> SecResult<SecValueObj>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let prefix = prefix.to_uppercase();
+    let mut root = SecValueObj::new();
+    for (name, value) in vars {
+        let name = name.as_ref();
+        if !name.to_uppercase().starts_with(&prefix) {
+            continue;
+        }
+        let parts: Vec<&str> = name.split(ENV_NEST_SEP).collect();
+        if parts.iter().any(|p| p.is_empty()) || name.len() == prefix.len() {
+            warn!(target: "exec", "  skip malformed env {}", name);
+            continue;
+        }
+        insert_path(&mut root, name, &parts, parse_env_value(value.as_ref()))?;
+    }
+    Ok(root)
+}
+
+/// 按布尔、整数、浮点、IP 的顺序识别环境变量取值，其余视为字符串，结果均为密文。
+///
+/// 只有能原样写回的取值才会转换类型，`007`、`1.10`、` 42 ` 等保留为字符串。
+pub fn parse_env_value(raw: &str) -> SecValueType {
+    SecValueType::sec_from(parse_value_type(raw))
+}
+
+fn parse_value_type(raw: &str) -> ValueType {
+    if raw.eq_ignore_ascii_case("true") {
+        return ValueType::Bool(true);
+    }
+    if raw.eq_ignore_ascii_case("false") {
+        return ValueType::Bool(false);
+    }
+    if let Ok(n) = raw.parse::<u64>()
+        && n.to_string() == raw
+    {
+        return ValueType::Number(n);
+    }
+    if raw.contains('.')
+        && let Ok(f) = raw.parse::<f64>()
+        && f.is_finite()
+        && f.to_string() == raw
+    {
+        return ValueType::Float(f);
+    }
+    if let Ok(ip) = raw.parse::<IpAddr>()
+        && ip.to_string() == raw
+    {
+        return ValueType::Ip(ip);
+    }
+    ValueType::String(raw.to_string())
+}
+
+/// 串行化读写进程环境变量的测试；`set_var` 与并发读取环境是未定义行为。
+#[cfg(test)]
+pub(crate) fn env_lock() -> std::sync::MutexGuard<'static, ()> {
+    static ENV_MUTEX: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    ENV_MUTEX.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn insert_path(
+    root: &mut SecValueObj,
+    name: &str,
+    parts: &[&str],
+    value: SecValueType,
+) -> SecResult<()> {
+    let conflict = || {
+        OrionSecReason::from_validation()
+            .to_err()
+            .with_detail(format!("env {name} conflicts with another nested env"))
+    };
+    let (last, parents) = parts.split_last().ok_or_else(conflict)?;
+    let mut current = root;
+    for part in parents {
+        let entry = current
+            .entry(UniString::from(*part))
+            .or_insert_with(|| SecValueType::sec_from(UniCaseMap::new()));
+        current = match entry {
+            SecValueType::Obj(obj) => obj.value_mut(),
+            _ => return Err(conflict()),
+        };
+    }
+    let key = UniString::from(*last);
+    if current.contains_key(&key) {
+        return Err(conflict());
+    }
+    current.insert(key, value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::ValueGetter;
+
+    fn vars(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_sec_obj_from_vars_nesting() {
+        let obj = sec_obj_from_vars(
+            vars(&[
+                ("SEC_DB__USER", "root"),
+                ("SEC_DB__PASSWORD", "p@ss"),
+                ("SEC_TOKEN", "t"),
+                ("PATH", "/usr/bin"),
+            ]),
+            SEC_PREFIX,
+        )
+        .unwrap();
+        assert_eq!(obj.len(), 2);
+        assert_eq!(
+            obj.value_get("SEC_DB.PASSWORD"),
+            Some(SecValueType::sec_from("p@ss".to_string()))
+        );
+        assert!(obj.value_get("SEC_DB").unwrap().is_secret());
+        assert!(obj.value_get("PATH").is_none());
+    }
+
+    #[test]
+    fn test_sec_obj_from_vars_typed() {
+        let obj = sec_obj_from_vars(
+            vars(&[
+                ("SEC_DEBUG", "TRUE"),
+                ("SEC_PORT", "5432"),
+                ("SEC_RATIO", "0.5"),
+                ("SEC_HOST", "10.0.0.1"),
+                ("SEC_NAME", "app"),
+            ]),
+            "sec_",
+        )
+        .unwrap();
+        assert_eq!(
+            obj.value_get("SEC_DEBUG"),
+            Some(SecValueType::sec_from(true))
+        );
+        assert_eq!(
+            obj.value_get("SEC_PORT"),
+            Some(SecValueType::sec_from(5432u64))
+        );
+        assert_eq!(
+            obj.value_get("SEC_RATIO"),
+            Some(SecValueType::sec_from(0.5f64))
+        );
+        assert_eq!(
+            obj.value_get("SEC_HOST"),
+            Some(SecValueType::sec_from(
+                "10.0.0.1".parse::<IpAddr>().unwrap()
+            ))
+        );
+        assert_eq!(
+            obj.value_get("SEC_NAME"),
+            Some(SecValueType::sec_from("app".to_string()))
+        );
+        assert!(obj.values().all(SecValueType::is_secret));
+    }
+
+    #[test]
+    fn test_parse_env_value_is_lossless() {
+        for raw in ["007", "1.10", " 42 ", "1e3", "+5", "::0001", " true"] {
+            assert_eq!(
+                parse_env_value(raw),
+                SecValueType::sec_from(raw.to_string()),
+                "{raw}"
+            );
+        }
+        assert_eq!(parse_env_value("42"), SecValueType::sec_from(42u64));
+        assert_eq!(parse_env_value("1.5"), SecValueType::sec_from(1.5f64));
+    }
+
+    #[test]
+    fn test_sec_obj_from_vars_conflict() {
+        let result = sec_obj_from_vars(
+            vars(&[("SEC_DB", "x"), ("SEC_DB__USER", "root")]),
+            SEC_PREFIX,
+        );
+        let err = result.unwrap_err();
+        assert!(format!("{err}").contains("SEC_DB__USER"));
+        assert!(!format!("{err}").contains("root"));
+    }
+
+    #[test]
+    fn test_sec_obj_from_vars_skip_malformed() {
+        let obj = sec_obj_from_vars(
+            vars(&[("SEC_", "x"), ("SEC_A____B", "y"), ("SEC_C__", "z")]),
+            SEC_PREFIX,
+        )
+        .unwrap();
+        assert!(obj.is_empty());
+    }
+
+    #[test]
+    fn test_load_sec_env_by_process_env() {
+        let key = "ORION_SEC_ENV_TEST__NESTED__VALUE";
+        let _lock = env_lock();
+        unsafe {
+            env::set_var(key, "42");
+        }
+        let obj = load_sec_env_by("ORION_SEC_ENV_TEST").unwrap();
+        unsafe {
+            env::remove_var(key);
+        }
+        assert_eq!(
+            obj.value_get("ORION_SEC_ENV_TEST.NESTED.VALUE"),
+            Some(SecValueType::sec_from(42u64))
+        );
+    }
+}
@@ -12,6 +12,7 @@ pub enum OrionSecReason {
 }
 
 #[derive(Debug, PartialEq, Serialize, Error)]
+#[non_exhaustive]
 pub enum SecReason {
     #[error("sensitive msg {0}")]
     SensitiveMsg(String),
@@ -21,6 +22,8 @@ pub enum SecReason {
     Deception(String),
     #[error("un authenticated {0}")]
     UnAuthenticated(String),
+    #[error("expired {0}")]
+    Expired(String),
 }
 
 pub type SecError = StructError<OrionSecReason>;
@@ -34,6 +37,7 @@ impl ErrorCode for OrionSecReason {
                 SecReason::NoPermission(_) => 201,
                 SecReason::Deception(_) => 301,
                 SecReason::UnAuthenticated(_) => 401,
+                SecReason::Expired(_) => 501,
             },
             OrionSecReason::Uvs(u) => u.error_code(),
         }
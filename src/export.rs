@@ -0,0 +1,337 @@
+use std::{collections::HashMap, path::Path};
+
+use orion_error::{ToStructError, UvsFrom};
+
+use crate::{
+    error::{OrionSecReason, SecResult},
+    flat::{FlatStyle, flatten},
+    fsio::write_private,
+    load::SEC_PREFIX,
+    sec::{NoSecConv, SecValueObj, SecValueType},
+};
+
+/// 导出格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// `.env` 文件：`KEY=value`，必要时加引号。
+    #[default]
+    Dotenv,
+    /// POSIX shell：`export KEY='value'`，可直接 `eval` 或 `source`。
+    Shell,
+    /// `docker run --env-file`：`KEY=value` 原样写出，不支持引号与换行。
+    DockerEnv,
+}
+
+/// 按密级筛选导出的键。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportScope {
+    #[default]
+    All,
+    PublicOnly,
+    SecretOnly,
+}
+
+/// 把 `SecValueObj` 导出为其他工具可读的环境变量文件。
+///
+/// 嵌套对象按 [`FlatStyle::Env`] 展开（`SEC_DB__PASSWORD`），空容器被忽略。
+/// 默认保留 `load_secfile_by` 加上的 `SEC_` 前缀，可用 `with_strip_prefix` 去除。
+#[derive(Debug, Clone, Default)]
+pub struct SecExporter {
+    format: ExportFormat,
+    scope: ExportScope,
+    strip_prefix: bool,
+}
+
+impl SecExporter {
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            ..Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn with_scope(mut self, scope: ExportScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    #[must_use]
+    pub fn with_strip_prefix(mut self, strip: bool) -> Self {
+        self.strip_prefix = strip;
+        self
+    }
+
+    /// 每行一个变量，以换行结尾；键名或取值无法用目标格式表达，
+    /// 或两个键展开、去除前缀后得到同一变量名时返回错误，不会丢弃其中任何一个。
+    pub fn export(&self, obj: &SecValueObj) -> SecResult<String> {
+        let mut out = String::new();
+        let mut names: HashMap<String, String> = HashMap::new();
+        for (key, value) in flatten(obj, FlatStyle::Env)? {
+            if matches!(value, SecValueType::Obj(_) | SecValueType::List(_)) {
+                continue;
+            }
+            let keep = match self.scope {
+                ExportScope::All => true,
+                ExportScope::PublicOnly => !value.is_secret(),
+                ExportScope::SecretOnly => value.is_secret(),
+            };
+            if !keep {
+                continue;
+            }
+            let mut name = key.as_str();
+            if self.strip_prefix {
+                name = name.strip_prefix(SEC_PREFIX).unwrap_or(name);
+            }
+            if let Some(first) = names.insert(name.to_string(), key.as_str().to_string()) {
+                return OrionSecReason::from_validation()
+                    .to_err()
+                    .with_detail(format!(
+                        "{first} and {} both export as {name}",
+                        key.as_str()
+                    ))
+                    .err();
+            }
+            let value = value.no_sec().to_string();
+            let line = match self.format {
+                ExportFormat::Dotenv => {
+                    format!("{}={}", check_name(name)?, dotenv_quote(name, &value)?)
+                }
+                ExportFormat::Shell => {
+                    format!("export {}={}", check_name(name)?, shell_quote(&value))
+                }
+                ExportFormat::DockerEnv => {
+                    if value.contains(['\n', '\r']) {
+                        return OrionSecReason::from_validation()
+                            .to_err()
+                            .with_detail(format!(
+                                "{name}: docker env-file values cannot span lines"
+                            ))
+                            .err();
+                    }
+                    format!("{}={value}", check_name(name)?)
+                }
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// 导出并以 0600 权限原子写入文件。
+    pub fn write_to(&self, path: &Path, obj: &SecValueObj) -> SecResult<()> {
+        write_private(path, self.export(obj)?.as_bytes())
+    }
+}
+
+/// 环境变量名须为 `[A-Za-z_][A-Za-z0-9_]*`。
+fn check_name(name: &str) -> SecResult<&str> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name)
+    } else {
+        OrionSecReason::from_validation()
+            .to_err()
+            .with_detail(format!("invalid variable name: {name}"))
+            .err()
+    }
+}
+
+fn is_bare(value: &str) -> bool {
+    value.chars().all(|c| {
+        c.is_ascii_alphanumeric()
+            || matches!(c, '_' | '-' | '.' | '/' | ':' | '@' | '+' | ',' | '=')
+    })
+}
+
+/// 只使用 python-dotenv 与 Node dotenv 都按同样方式还原的写法：
+/// 不含 `'`、换行、`\\` 且不以 `\` 结尾时用单引号原样保留；
+/// 否则用双引号，且只转义换行（`\n`、`\r`）。
+/// 双引号内的 `"`、`\` 与会被插值的 `${` 没有通用写法，返回错误。
+fn dotenv_quote(name: &str, value: &str) -> SecResult<String> {
+    if is_bare(value) {
+        return Ok(value.to_string());
+    }
+    if !value.contains(['\'', '\n', '\r']) && !value.contains("\\\\") && !value.ends_with('\\') {
+        return Ok(format!("'{value}'"));
+    }
+    if value.contains(['"', '\\']) || value.contains("${") {
+        return OrionSecReason::from_validation()
+            .to_err()
+            .with_detail(format!(
+                "{name}: value cannot be written portably to dotenv"
+            ))
+            .err();
+    }
+    Ok(format!(
+        "\"{}\"",
+        value.replace('\n', "\\n").replace('\r', "\\r")
+    ))
+}
+
+/// POSIX 单引号：内部的 `'` 写作 `'\''`。
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty() && is_bare(value) {
+        return value.to_string();
+    }
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::SecFrom;
+
+    fn sample() -> SecValueObj {
+        let mut db = SecValueObj::new();
+        db.insert(
+            "HOST".into(),
+            SecValueType::nor_from("db.local".to_string()),
+        );
+        db.insert(
+            "PASSWORD".into(),
+            SecValueType::sec_from("it's $ecret".to_string()),
+        );
+        let mut obj = SecValueObj::new();
+        obj.insert("SEC_DB".into(), SecValueType::from(db));
+        obj.insert("SEC_PORT".into(), SecValueType::nor_from(5432u64));
+        obj.insert("SEC_NOTE".into(), SecValueType::sec_from("a b".to_string()));
+        obj
+    }
+
+    #[test]
+    fn test_export_formats_and_filters() {
+        let obj = sample();
+        assert_eq!(
+            SecExporter::new(ExportFormat::Dotenv).export(&obj).unwrap(),
+            "SEC_DB__HOST=db.local\nSEC_DB__PASSWORD=\"it's $ecret\"\nSEC_PORT=5432\nSEC_NOTE='a b'\n"
+        );
+        assert_eq!(
+            SecExporter::new(ExportFormat::Shell)
+                .with_scope(ExportScope::SecretOnly)
+                .with_strip_prefix(true)
+                .export(&obj)
+                .unwrap(),
+            "export DB__PASSWORD='it'\\''s $ecret'\nexport NOTE='a b'\n"
+        );
+        assert_eq!(
+            SecExporter::new(ExportFormat::DockerEnv)
+                .with_scope(ExportScope::PublicOnly)
+                .export(&obj)
+                .unwrap(),
+            "SEC_DB__HOST=db.local\nSEC_PORT=5432\n"
+        );
+
+        let mut bad = SecValueObj::new();
+        bad.insert("PEM".into(), SecValueType::sec_from("a\nb".to_string()));
+        assert!(
+            SecExporter::new(ExportFormat::DockerEnv)
+                .export(&bad)
+                .is_err()
+        );
+        assert_eq!(
+            SecExporter::new(ExportFormat::Dotenv).export(&bad).unwrap(),
+            "PEM=\"a\\nb\"\n"
+        );
+        for raw in ["say \"hi\"\n", "C:\\tmp's", "it's ${HOME}", "a\\\\b\n"] {
+            let mut bad = SecValueObj::new();
+            bad.insert("V".into(), SecValueType::sec_from(raw.to_string()));
+            assert!(SecExporter::new(ExportFormat::Dotenv).export(&bad).is_err());
+        }
+        let mut bad = SecValueObj::new();
+        bad.insert("my-key".into(), SecValueType::nor_from("v".to_string()));
+        assert!(SecExporter::new(ExportFormat::Shell).export(&bad).is_err());
+    }
+
+    #[test]
+    fn test_export_strip_prefix_collision() {
+        let mut obj = SecValueObj::new();
+        obj.insert("SEC_X".into(), SecValueType::sec_from("a".to_string()));
+        obj.insert("X".into(), SecValueType::nor_from("b".to_string()));
+        let err = SecExporter::new(ExportFormat::Dotenv)
+            .with_strip_prefix(true)
+            .export(&obj)
+            .unwrap_err();
+        assert!(err.to_string().contains("SEC_X and X both export as X"));
+        assert!(SecExporter::new(ExportFormat::Dotenv).export(&obj).is_ok());
+        let mut a = SecValueObj::new();
+        a.insert(
+            "B".into(),
+            SecValueType::sec_from("nested-secret".to_string()),
+        );
+        let mut obj = SecValueObj::new();
+        obj.insert("A".into(), SecValueType::from(a));
+        obj.insert(
+            "A__B".into(),
+            SecValueType::nor_from("top-public".to_string()),
+        );
+        for format in [
+            ExportFormat::Dotenv,
+            ExportFormat::Shell,
+            ExportFormat::DockerEnv,
+        ] {
+            assert!(SecExporter::new(format).export(&obj).is_err());
+        }
+    }
+
+    #[test]
+    fn test_export_dotenv_round_trip() {
+        let probe = std::process::Command::new("python3")
+            .args(["-c", "import dotenv"])
+            .output();
+        if !probe.is_ok_and(|out| out.status.success()) {
+            return;
+        }
+        let mut obj = sample();
+        for (key, raw) in [
+            ("PEM", "a\nb\r\nc"),
+            ("QUOTE", "say \"hi\""),
+            ("SLASH", "C:\\tmp"),
+            ("HASH", "pa#ss word"),
+            ("EMPTY", ""),
+        ] {
+            obj.insert(key.into(), SecValueType::sec_from(raw.to_string()));
+        }
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".env");
+        SecExporter::new(ExportFormat::Dotenv)
+            .write_to(&path, &obj)
+            .unwrap();
+        let out = std::process::Command::new("python3")
+            .arg("-c")
+            .arg("import sys, json; from dotenv import dotenv_values; print(json.dumps(dotenv_values(sys.argv[1])))")
+            .arg(&path)
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        let parsed: HashMap<String, String> = serde_json::from_slice(&out.stdout).unwrap();
+        let expect: HashMap<String, String> = flatten(&obj, FlatStyle::Env)
+            .unwrap()
+            .into_iter()
+            .map(|(k, v)| (k.as_str().to_string(), v.no_sec().to_string()))
+            .collect();
+        assert_eq!(parsed, expect);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_export_shell_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sec.sh");
+        SecExporter::new(ExportFormat::Shell)
+            .write_to(&path, &sample())
+            .unwrap();
+        let out = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(r#". "$1"; printf '%s|%s' "$SEC_DB__PASSWORD" "$SEC_NOTE""#)
+            .arg("sh")
+            .arg(&path)
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(out.stdout).unwrap(), "it's $ecret|a b");
+    }
+}
@@ -0,0 +1,379 @@
+use orion_error::{ToStructError, UvsFrom};
+
+use crate::{
+    error::{OrionSecReason, SecError, SecResult},
+    query::{PathSeg, SecPath},
+    sec::{SecList, SecObj, SecValueObj, SecValueType},
+    types::{UniCaseMap, UniString},
+    visit::{SecVisitor, visit},
+};
+
+/// 扁平键的书写风格。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlatStyle {
+    /// `A.B[0].C`，特殊字符的键以双引号包裹，可无歧义还原。
+    #[default]
+    Path,
+    /// `A__B__0__C`，适合环境变量；纯数字段还原为数组下标，键本身不能包含 `__`。
+    Env,
+}
+
+const ENV_SEP: &str = "__";
+
+impl FlatStyle {
+    pub fn render(&self, path: &SecPath) -> String {
+        match self {
+            FlatStyle::Path => path.to_string(),
+            FlatStyle::Env => path
+                .segments()
+                .iter()
+                .map(|seg| match seg {
+                    PathSeg::Key(k) => k.as_str().to_string(),
+                    PathSeg::Index(i) => i.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(ENV_SEP),
+        }
+    }
+
+    pub fn parse(&self, key: &str) -> SecResult<SecPath> {
+        match self {
+            FlatStyle::Path => key.parse(),
+            FlatStyle::Env => {
+                let segs = key
+                    .split(ENV_SEP)
+                    .map(|part| {
+                        if part.is_empty() {
+                            Err(flat_error(format!("empty segment in `{key}`")))
+                        } else if let Ok(i) = part.parse::<usize>() {
+                            Ok(PathSeg::Index(i))
+                        } else {
+                            Ok(PathSeg::Key(UniString::from(part)))
+                        }
+                    })
+                    .collect::<SecResult<Vec<_>>>()?;
+                Ok(SecPath::from(segs))
+            }
+        }
+    }
+}
+
+fn flat_error(detail: String) -> SecError {
+    OrionSecReason::from_validation()
+        .to_err()
+        .with_detail(detail)
+}
+
+struct Flattener {
+    style: FlatStyle,
+    secret_depth: usize,
+    out: UniCaseMap<SecValueType>,
+    sources: UniCaseMap<SecPath>,
+    error: Option<SecError>,
+}
+
+impl Flattener {
+    fn emit(&mut self, path: &SecPath, value: &SecValueType) {
+        if self.error.is_some() {
+            return;
+        }
+        if self.style == FlatStyle::Env
+            && path
+                .segments()
+                .iter()
+                .any(|seg| matches!(seg, PathSeg::Key(k) if k.as_str().contains(ENV_SEP)))
+        {
+            self.error = Some(flat_error(format!(
+                "`{path}` contains `{ENV_SEP}` and cannot be flattened in env style"
+            )));
+            return;
+        }
+        let key = UniString::from(self.style.render(path));
+        if let Some(first) = self.sources.get(&key) {
+            self.error = Some(flat_error(format!(
+                "`{first}` and `{path}` both flatten to `{}`",
+                key.as_str()
+            )));
+            return;
+        }
+        let value = if self.secret_depth > 0 {
+            value.clone().to_sec()
+        } else {
+            value.clone()
+        };
+        self.sources.insert(key.clone(), path.clone());
+        self.out.insert(key, value);
+    }
+}
+
+impl SecVisitor for Flattener {
+    fn visit_leaf(&mut self, path: &SecPath, value: &SecValueType) {
+        self.emit(path, value);
+    }
+
+    fn enter_obj(&mut self, path: &SecPath, obj: &SecObj) -> bool {
+        if obj.value().is_empty() {
+            self.emit(path, &SecValueType::Obj(obj.clone()));
+            return false;
+        }
+        self.secret_depth += usize::from(obj.is_secret());
+        true
+    }
+
+    fn leave_obj(&mut self, _path: &SecPath, obj: &SecObj) {
+        if !obj.value().is_empty() {
+            self.secret_depth -= usize::from(obj.is_secret());
+        }
+    }
+
+    fn enter_list(&mut self, path: &SecPath, list: &SecList) -> bool {
+        if list.value().is_empty() {
+            self.emit(path, &SecValueType::List(list.clone()));
+            return false;
+        }
+        self.secret_depth += usize::from(list.is_secret());
+        true
+    }
+
+    fn leave_list(&mut self, _path: &SecPath, list: &SecList) {
+        if !list.value().is_empty() {
+            self.secret_depth -= usize::from(list.is_secret());
+        }
+    }
+}
+
+/// 将嵌套的 `SecValueObj` 展开为扁平映射，键为叶子节点的完整路径。
+///
+/// 密级按叶子保留：位于密文容器中的叶子会被标记为密文；空对象与空数组作为叶子保留，
+/// 因此 [`unflatten`] 可以还原出同样的结构与取值。
+///
+/// 两条路径展开为同一个键（如 `FlatStyle::Env` 下的 `A_.B` 与 `A._B`），
+/// 或 `FlatStyle::Env` 下的键本身含有 `__` 时返回错误，不会静默覆盖。
+pub fn flatten(obj: &SecValueObj, style: FlatStyle) -> SecResult<UniCaseMap<SecValueType>> {
+    let mut flattener = Flattener {
+        style,
+        secret_depth: 0,
+        out: UniCaseMap::new(),
+        sources: UniCaseMap::new(),
+        error: None,
+    };
+    visit(obj, &mut flattener);
+    match flattener.error {
+        Some(err) => Err(err),
+        None => Ok(flattener.out),
+    }
+}
+
+/// [`flatten`] 的逆操作。
+///
+/// 还原出的容器均为明文容器，密级保留在各叶子上。
+/// 数组下标必须连续出现（与 `flatten` 的输出顺序一致），否则返回错误。
+pub fn unflatten(flat: &UniCaseMap<SecValueType>, style: FlatStyle) -> SecResult<SecValueObj> {
+    let mut root = SecValueObj::new();
+    for (key, value) in flat {
+        let path = style.parse(key.as_str())?;
+        insert_at(&mut root, &path, value.clone()).map_err(|_| {
+            flat_error(format!(
+                "flat key `{}` conflicts with its siblings",
+                key.as_str()
+            ))
+        })?;
+    }
+    Ok(root)
+}
+
+fn empty_for(seg: &PathSeg) -> SecValueType {
+    match seg {
+        PathSeg::Key(_) => SecValueType::from(SecValueObj::new()),
+        PathSeg::Index(_) => SecValueType::from(Vec::new()),
+    }
+}
+
+fn insert_at(root: &mut SecValueObj, path: &SecPath, value: SecValueType) -> Result<(), ()> {
+    let segs = path.segments();
+    let Some((PathSeg::Key(first), rest)) = segs.split_first() else {
+        return Err(());
+    };
+    if rest.is_empty() {
+        return match root.contains_key(first) {
+            true => Err(()),
+            false => {
+                root.insert(first.clone(), value);
+                Ok(())
+            }
+        };
+    }
+    let node = root
+        .entry(first.clone())
+        .or_insert_with(|| empty_for(&rest[0]));
+    insert_node(node, rest, value)
+}
+
+fn insert_node(node: &mut SecValueType, segs: &[PathSeg], value: SecValueType) -> Result<(), ()> {
+    let (seg, rest) = segs.split_first().ok_or(())?;
+    let slot = match (seg, node) {
+        (PathSeg::Key(k), SecValueType::Obj(obj)) => {
+            let obj = obj.value_mut();
+            if rest.is_empty() {
+                if obj.contains_key(k) {
+                    return Err(());
+                }
+                obj.insert(k.clone(), value);
+                return Ok(());
+            }
+            obj.entry(k.clone()).or_insert_with(|| empty_for(&rest[0]))
+        }
+        (PathSeg::Index(i), SecValueType::List(list)) => {
+            let list = list.value_mut();
+            if rest.is_empty() {
+                if *i != list.len() {
+                    return Err(());
+                }
+                list.push(value);
+                return Ok(());
+            }
+            if *i == list.len() {
+                list.push(empty_for(&rest[0]));
+            }
+            list.get_mut(*i).ok_or(())?
+        }
+        _ => return Err(()),
+    };
+    insert_node(slot, rest, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::{SecFrom, ValueGetter};
+
+    fn sample() -> SecValueObj {
+        let mut server = UniCaseMap::new();
+        server.insert("host".into(), SecValueType::nor_from("a".to_string()));
+        server.insert("pass".into(), SecValueType::sec_from("p".to_string()));
+        let mut creds = UniCaseMap::new();
+        creds.insert("user".into(), SecValueType::nor_from("u".to_string()));
+
+        let mut root = UniCaseMap::new();
+        root.insert(
+            "servers".into(),
+            SecValueType::from(vec![SecValueType::from(server)]),
+        );
+        root.insert("creds".into(), SecValueType::nor_from(creds));
+        root.insert("empty".into(), SecValueType::from(SecValueObj::new()));
+        root.insert("port".into(), SecValueType::nor_from(8080u64));
+        root
+    }
+
+    fn sample_with_secret_container() -> SecValueObj {
+        let mut root = sample();
+        let creds = root.get_mut("CREDS").unwrap();
+        if let SecValueType::Obj(obj) = creds {
+            obj.set_secret(true);
+        }
+        root
+    }
+
+    #[test]
+    fn test_flatten_path_style() {
+        let flat = flatten(&sample(), FlatStyle::Path).unwrap();
+        let keys: Vec<&str> = flat.keys().map(|k| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "SERVERS[0].HOST",
+                "SERVERS[0].PASS",
+                "CREDS.USER",
+                "EMPTY",
+                "PORT"
+            ]
+        );
+        assert!(flat["SERVERS[0].PASS"].is_secret());
+        assert!(!flat["SERVERS[0].HOST"].is_secret());
+    }
+
+    #[test]
+    fn test_flatten_env_style() {
+        let flat = flatten(&sample(), FlatStyle::Env).unwrap();
+        assert!(flat.contains_key("SERVERS__0__PASS"));
+        assert!(flat.contains_key("CREDS__USER"));
+    }
+
+    #[test]
+    fn test_flatten_pushes_container_secret_to_leaves() {
+        let flat = flatten(&sample_with_secret_container(), FlatStyle::Path).unwrap();
+        assert!(flat["CREDS.USER"].is_secret());
+        assert!(!flat["PORT"].is_secret());
+    }
+
+    #[test]
+    fn test_roundtrip_both_styles() {
+        for style in [FlatStyle::Path, FlatStyle::Env] {
+            let obj = sample();
+            let back = unflatten(&flatten(&obj, style).unwrap(), style).unwrap();
+            assert_eq!(back, obj, "{style:?}");
+        }
+        let back = unflatten(
+            &flatten(&sample_with_secret_container(), FlatStyle::Path).unwrap(),
+            FlatStyle::Path,
+        )
+        .unwrap();
+        assert!(back.value_get("creds.user").unwrap().is_secret());
+    }
+
+    #[test]
+    fn test_roundtrip_quoted_keys() {
+        let mut root = UniCaseMap::new();
+        root.insert("a.b".into(), SecValueType::sec_from("x".to_string()));
+        let flat = flatten(&root, FlatStyle::Path).unwrap();
+        assert!(flat.contains_key("\"A.B\""));
+        assert_eq!(unflatten(&flat, FlatStyle::Path).unwrap(), root);
+    }
+
+    #[test]
+    fn test_flatten_env_collision_is_error() {
+        let mut a = UniCaseMap::new();
+        a.insert(
+            "B".into(),
+            SecValueType::sec_from("nested-secret".to_string()),
+        );
+        let mut root = UniCaseMap::new();
+        root.insert("A".into(), SecValueType::from(a));
+        root.insert(
+            "A__B".into(),
+            SecValueType::nor_from("top-public".to_string()),
+        );
+        let err = flatten(&root, FlatStyle::Env).unwrap_err();
+        assert!(format!("{err}").contains("`A__B` contains `__`"), "{err}");
+        assert_eq!(flatten(&root, FlatStyle::Path).unwrap().len(), 2);
+
+        let mut a = UniCaseMap::new();
+        a.insert("_B".into(), SecValueType::sec_from("s".to_string()));
+        let mut root = UniCaseMap::new();
+        root.insert("A".into(), SecValueType::from(a));
+        let mut b = UniCaseMap::new();
+        b.insert("B".into(), SecValueType::nor_from("p".to_string()));
+        root.insert("A_".into(), SecValueType::from(b));
+        let err = flatten(&root, FlatStyle::Env).unwrap_err();
+        assert!(
+            format!("{err}").contains("`A._B` and `A_.B` both flatten to `A___B`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_unflatten_conflicts() {
+        let mut flat = UniCaseMap::new();
+        flat.insert("A".into(), SecValueType::nor_from(1u64));
+        flat.insert("A.B".into(), SecValueType::nor_from(2u64));
+        assert!(unflatten(&flat, FlatStyle::Path).is_err());
+
+        let mut flat = UniCaseMap::new();
+        flat.insert("A__1".into(), SecValueType::nor_from(1u64));
+        assert!(unflatten(&flat, FlatStyle::Env).is_err());
+
+        let mut flat = UniCaseMap::new();
+        flat.insert("A[*]".into(), SecValueType::nor_from(1u64));
+        assert!(unflatten(&flat, FlatStyle::Path).is_err());
+    }
+}
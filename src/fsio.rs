@@ -0,0 +1,146 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use orion_error::{ErrorOwe, ErrorWith, ToStructError, UvsFrom};
+
+use crate::error::{OrionSecReason, SecResult};
+
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// 创建仅属主可访问（0700）的目录。
+///
+/// 目录已存在时不修改其权限，只检查：不是目录或对组、其他用户开放时返回错误。
+pub(crate) fn create_private_dir(dir: &Path) -> SecResult<()> {
+    if let Ok(meta) = fs::metadata(dir) {
+        if !meta.is_dir() {
+            return OrionSecReason::from_validation()
+                .to_err()
+                .with_detail(format!("{} is not a directory", dir.display()))
+                .err();
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if meta.permissions().mode() & 0o077 != 0 {
+                return OrionSecReason::from_validation()
+                    .to_err()
+                    .with_detail(format!(
+                        "{} is accessible by group or others",
+                        dir.display()
+                    ))
+                    .err();
+            }
+        }
+        return Ok(());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        // 创建时即为 0700，不存在对组或其他用户可见的窗口；chmod 只用于补齐 umask 去掉的位
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .owe_sys()
+            .with(dir)?;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+            .owe_sys()
+            .with(dir)?;
+    }
+    #[cfg(not(unix))]
+    fs::create_dir_all(dir).owe_sys().with(dir)?;
+    Ok(())
+}
+
+/// 打开（必要时创建 0600 的）锁文件并阻塞获取排他锁，返回的 `File` 被丢弃时释放。
+pub(crate) fn lock_exclusive(path: &Path) -> SecResult<File> {
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create(true).truncate(false);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(path).owe_sys().with(path)?;
+    file.lock().owe_sys().with(path)?;
+    Ok(file)
+}
+
+/// 原子写入密文文件：在同目录写入 0600 的临时文件并 fsync，再 rename 覆盖目标。
+///
+/// 读者只会看到旧文件或完整的新文件；失败时清理临时文件，目标保持不变。
+pub(crate) fn write_private(path: &Path, content: &[u8]) -> SecResult<()> {
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = dir.join(format!(
+        ".{name}.tmp.{}.{}",
+        std::process::id(),
+        TMP_SEQ.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = write_new(&tmp, content).and_then(|_| fs::rename(&tmp, path).owe_sys().with(path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+        return result;
+    }
+    // rename 之后同步目录项，保证掉电后也能看到新文件
+    if let Ok(d) = File::open(dir) {
+        let _ = d.sync_all();
+    }
+    Ok(())
+}
+
+fn write_new(path: &Path, content: &[u8]) -> SecResult<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path).owe_sys().with(path)?;
+    file.write_all(content).owe_sys().with(path)?;
+    file.sync_all().owe_sys().with(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_private_replaces_atomically() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("secret.yml");
+        write_private(&path, b"a: 1\n").unwrap();
+        write_private(&path, b"a: 2\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a: 2\n");
+        let leftovers = fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(leftovers, 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_write_private_missing_dir_fails_cleanly() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("missing").join("secret.yml");
+        assert!(write_private(&path, b"x").is_err());
+        assert!(!path.exists());
+    }
+}
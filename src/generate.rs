@@ -0,0 +1,382 @@
+use data_encoding::{BASE32_NOPAD, BASE64, BASE64URL_NOPAD, HEXLOWER};
+use orion_error::{ToStructError, UvsFrom};
+
+use crate::{
+    error::{OrionSecReason, SecError, SecResult},
+    random,
+    sec::{SecFrom, SecString},
+};
+
+const LOWER: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPER: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGIT: &[u8] = b"0123456789";
+const SYMBOL: &[u8] = b"!#$%&()*+,-./:;<=>?@[]^_{|}~";
+/// 容易混淆的字符，`with_avoid_ambiguous` 时剔除。
+const AMBIGUOUS: &[u8] = b"0O1lI|";
+
+/// 随机口令规格：长度与字符类别，每个启用的类别至少出现一次。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordSpec {
+    length: usize,
+    lower: bool,
+    upper: bool,
+    digits: bool,
+    symbols: bool,
+    avoid_ambiguous: bool,
+}
+
+impl Default for PasswordSpec {
+    fn default() -> Self {
+        Self::new(24)
+    }
+}
+
+impl PasswordSpec {
+    /// 默认启用小写、大写、数字与符号。
+    pub fn new(length: usize) -> Self {
+        Self {
+            length,
+            lower: true,
+            upper: true,
+            digits: true,
+            symbols: true,
+            avoid_ambiguous: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_lower(mut self, lower: bool) -> Self {
+        self.lower = lower;
+        self
+    }
+
+    #[must_use]
+    pub fn with_upper(mut self, upper: bool) -> Self {
+        self.upper = upper;
+        self
+    }
+
+    #[must_use]
+    pub fn with_digits(mut self, digits: bool) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    #[must_use]
+    pub fn with_symbols(mut self, symbols: bool) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// 剔除 `0O1lI|` 等易混淆字符，便于人工抄写。
+    #[must_use]
+    pub fn with_avoid_ambiguous(mut self, avoid: bool) -> Self {
+        self.avoid_ambiguous = avoid;
+        self
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// 未启用任何类别或长度小于类别数时返回错误。
+    pub fn generate(&self) -> SecResult<SecString> {
+        let classes: Vec<Vec<u8>> = [
+            (self.lower, LOWER),
+            (self.upper, UPPER),
+            (self.digits, DIGIT),
+            (self.symbols, SYMBOL),
+        ]
+        .into_iter()
+        .filter(|(on, _)| *on)
+        .map(|(_, class)| {
+            class
+                .iter()
+                .copied()
+                .filter(|c| !self.avoid_ambiguous || !AMBIGUOUS.contains(c))
+                .collect()
+        })
+        .collect();
+        if classes.is_empty() {
+            return Err(gen_error("no character class enabled".to_string()));
+        }
+        if self.length < classes.len() {
+            return Err(gen_error(format!(
+                "password length {} is shorter than {} required classes",
+                self.length,
+                classes.len()
+            )));
+        }
+        let all = classes.concat();
+        let mut chars = classes
+            .iter()
+            .map(|class| random::pick(class))
+            .collect::<SecResult<Vec<u8>>>()?;
+        while chars.len() < self.length {
+            chars.push(random::pick(&all)?);
+        }
+        random::shuffle(&mut chars)?;
+        Ok(SecString::sec_from(
+            String::from_utf8(chars).unwrap_or_default(),
+        ))
+    }
+}
+
+/// 口令短语规格：从词表中独立均匀抽取 `words` 个单词。
+///
+/// 内置词表 256 个词，每词 8 比特熵；默认 10 个词，需要更高强度时增加词数或提供更大的词表。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PassphraseSpec {
+    words: usize,
+    separator: String,
+    word_list: Option<Vec<String>>,
+}
+
+/// 默认词数，保证内置词表下不低于 77 比特熵。
+const DEFAULT_PASSPHRASE_WORDS: usize = 10;
+
+impl Default for PassphraseSpec {
+    /// 内置词表抽取 10 个词，熵为 10 × 8 = 80 比特。
+    fn default() -> Self {
+        Self::new(DEFAULT_PASSPHRASE_WORDS)
+    }
+}
+
+impl PassphraseSpec {
+    pub fn new(words: usize) -> Self {
+        Self {
+            words,
+            separator: "-".to_string(),
+            word_list: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// 使用自定义词表，重复与空白词会被去除。
+    #[must_use]
+    pub fn with_word_list<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut list: Vec<String> = Vec::new();
+        for word in words {
+            let word = word.into().trim().to_string();
+            if !word.is_empty() && !list.contains(&word) {
+                list.push(word);
+            }
+        }
+        self.word_list = Some(list);
+        self
+    }
+
+    /// 词数为 0 或词表少于 2 个词时返回错误。
+    pub fn generate(&self) -> SecResult<SecString> {
+        let list: Vec<&str> = match &self.word_list {
+            Some(list) => list.iter().map(String::as_str).collect(),
+            None => WORDS.to_vec(),
+        };
+        if self.words == 0 || list.len() < 2 {
+            return Err(gen_error(
+                "passphrase needs at least one word and two candidates".to_string(),
+            ));
+        }
+        let picked = (0..self.words)
+            .map(|_| random::pick(&list))
+            .collect::<SecResult<Vec<&str>>>()?;
+        Ok(SecString::sec_from(picked.join(&self.separator)))
+    }
+}
+
+/// 随机令牌的文本编码。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TokenEncoding {
+    #[default]
+    Hex,
+    /// 标准 base64，带填充。
+    Base64,
+    /// URL 安全的 base64，无填充。
+    Base64Url,
+    /// RFC 4648 base32，无填充。
+    Base32,
+}
+
+impl TokenEncoding {
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            TokenEncoding::Hex => HEXLOWER.encode(bytes),
+            TokenEncoding::Base64 => BASE64.encode(bytes),
+            TokenEncoding::Base64Url => BASE64URL_NOPAD.encode(bytes),
+            TokenEncoding::Base32 => BASE32_NOPAD.encode(bytes),
+        }
+    }
+}
+
+/// `bytes` 个随机字节按 `encoding` 编码的令牌。
+pub fn random_token(bytes: usize, encoding: TokenEncoding) -> SecResult<SecString> {
+    if bytes == 0 {
+        return Err(gen_error("token needs at least one byte".to_string()));
+    }
+    let mut buf = vec![0u8; bytes];
+    random::fill(&mut buf)?;
+    let token = encoding.encode(&buf);
+    buf.fill(0);
+    Ok(SecString::sec_from(token))
+}
+
+/// RFC 4122 版本 4 的随机 UUID（小写、带连字符）。
+pub fn random_uuid() -> SecResult<SecString> {
+    let mut b = [0u8; 16];
+    random::fill(&mut b)?;
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let hex = HEXLOWER.encode(&b);
+    Ok(SecString::sec_from(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )))
+}
+
+fn gen_error(detail: String) -> SecError {
+    OrionSecReason::from_validation()
+        .to_err()
+        .with_detail(detail)
+}
+
+const WORDS: [&str; 256] = [
+    "able", "acid", "aged", "also", "area", "army", "away", "baby", "back", "ball", "band", "bank",
+    "base", "bath", "bear", "beat", "bell", "belt", "best", "bird", "blow", "blue", "boat", "body",
+    "bone", "book", "boot", "born", "boss", "bowl", "bulk", "burn", "bush", "busy", "cake", "calm",
+    "came", "camp", "card", "care", "cart", "case", "cash", "cast", "cell", "chef", "chip", "city",
+    "clay", "club", "coal", "coat", "code", "cold", "cook", "cool", "cope", "copy", "core", "corn",
+    "cost", "crew", "crop", "dark", "data", "date", "dawn", "deal", "dear", "deck", "deep", "desk",
+    "dial", "diet", "disk", "dock", "door", "dose", "down", "draw", "drop", "drum", "dual", "duck",
+    "dust", "duty", "each", "earn", "east", "easy", "edge", "else", "epic", "even", "exit", "face",
+    "fact", "fair", "fall", "farm", "fast", "fear", "feel", "file", "fill", "film", "find", "fine",
+    "fire", "firm", "fish", "flag", "flat", "flow", "folk", "food", "foot", "fork", "form", "fort",
+    "four", "free", "frog", "fuel", "full", "fund", "gain", "game", "gate", "gear", "gift", "glad",
+    "glow", "goal", "gold", "golf", "good", "gray", "grid", "grow", "gulf", "hair", "half", "hall",
+    "hand", "hard", "harp", "head", "heat", "help", "herb", "hero", "hill", "hint", "hold", "hole",
+    "home", "hook", "hope", "horn", "host", "hour", "huge", "hunt", "idea", "inch", "iron", "item",
+    "jazz", "join", "joke", "jump", "jury", "keen", "keep", "kind", "king", "kite", "knee", "knot",
+    "lake", "lamp", "land", "lane", "last", "lava", "lawn", "lead", "leaf", "lens", "life", "lift",
+    "lime", "line", "link", "lion", "list", "load", "loan", "lock", "loft", "logo", "long", "loop",
+    "lord", "luck", "mail", "main", "malt", "mark", "mask", "mass", "meal", "meat", "menu", "mild",
+    "milk", "mind", "mine", "mint", "mist", "mode", "moon", "moss", "most", "move", "much", "myth",
+    "nail", "navy", "near", "neck", "nest", "news", "nice", "node", "noon", "nose", "note", "oath",
+    "oven", "pace", "pack", "page", "pair", "palm", "park", "path", "peak", "pine", "pink", "plan",
+    "play", "plot", "plum", "poem",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_password_spec_classes() {
+        for _ in 0..20 {
+            let pw = PasswordSpec::new(4).generate().unwrap();
+            assert!(pw.is_secret());
+            let pw = pw.value();
+            assert_eq!(pw.len(), 4);
+            for class in [LOWER, UPPER, DIGIT, SYMBOL] {
+                assert!(pw.bytes().any(|b| class.contains(&b)), "{pw}");
+            }
+        }
+        let pw = PasswordSpec::new(64)
+            .with_symbols(false)
+            .with_avoid_ambiguous(true)
+            .generate()
+            .unwrap();
+        assert!(
+            pw.value()
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() && !AMBIGUOUS.contains(&b))
+        );
+        assert_eq!(format!("{pw}"), "********");
+
+        assert!(PasswordSpec::new(3).generate().is_err());
+        let none = PasswordSpec::new(8)
+            .with_lower(false)
+            .with_upper(false)
+            .with_digits(false)
+            .with_symbols(false);
+        assert!(none.generate().is_err());
+    }
+
+    #[test]
+    fn test_passphrase_spec() {
+        assert_eq!(WORDS.iter().collect::<HashSet<_>>().len(), WORDS.len());
+        let phrase = PassphraseSpec::new(5).generate().unwrap();
+        let words: Vec<&str> = phrase.value().split('-').collect();
+        assert_eq!(words.len(), 5);
+        assert!(words.iter().all(|w| WORDS.contains(w)));
+
+        let custom = PassphraseSpec::new(3)
+            .with_separator(" ")
+            .with_word_list(["red", "blue", "red", " "])
+            .generate()
+            .unwrap();
+        assert!(custom.value().split(' ').all(|w| w == "red" || w == "blue"));
+        assert!(
+            PassphraseSpec::new(3)
+                .with_word_list(["only"])
+                .generate()
+                .is_err()
+        );
+        assert!(PassphraseSpec::new(0).generate().is_err());
+        let phrase = PassphraseSpec::default().generate().unwrap();
+        assert_eq!(phrase.value().split('-').count(), DEFAULT_PASSPHRASE_WORDS);
+        assert!((DEFAULT_PASSPHRASE_WORDS as f64) * (WORDS.len() as f64).log2() >= 77.0);
+    }
+
+    #[test]
+    fn test_random_token_encodings() {
+        let hex = random_token(16, TokenEncoding::Hex).unwrap();
+        assert_eq!(hex.value().len(), 32);
+        assert!(hex.value().bytes().all(|b| b.is_ascii_hexdigit()));
+        assert_eq!(
+            random_token(16, TokenEncoding::Base64)
+                .unwrap()
+                .value()
+                .len(),
+            24
+        );
+        let url = random_token(16, TokenEncoding::Base64Url).unwrap();
+        assert_eq!(url.value().len(), 22);
+        assert!(!url.value().contains(['+', '/', '=']));
+        assert_eq!(
+            random_token(10, TokenEncoding::Base32)
+                .unwrap()
+                .value()
+                .len(),
+            16
+        );
+        assert!(random_token(0, TokenEncoding::Hex).is_err());
+        assert_ne!(hex, random_token(16, TokenEncoding::Hex).unwrap());
+    }
+
+    #[test]
+    fn test_random_uuid_v4() {
+        let id = random_uuid().unwrap();
+        let id = id.value();
+        assert_eq!(id.len(), 36);
+        let parts: Vec<&str> = id.split('-').collect();
+        assert_eq!(
+            parts.iter().map(|p| p.len()).collect::<Vec<_>>(),
+            vec![8, 4, 4, 4, 12]
+        );
+        assert!(parts[2].starts_with('4'));
+        assert!(matches!(parts[3].as_bytes()[0], b'8' | b'9' | b'a' | b'b'));
+    }
+}
@@ -0,0 +1,443 @@
+use std::{
+    fmt::{Debug, Formatter},
+    io::Write,
+    process::{Child, Command, Stdio},
+};
+
+use orion_error::{ErrorOwe, ToStructError, UvsFrom};
+
+use crate::{
+    error::{OrionSecReason, SecResult},
+    flat::{FlatStyle, flatten},
+    load::SEC_PREFIX,
+    query::SecQuery,
+    sec::{NoSecConv, SECRET_MASK, SecValueObj, SecValueType},
+};
+
+/// 通过文件描述符传递密文时，子进程从该变量（加前缀）得知描述符编号。
+pub const SECRETS_FD_VAR: &str = "SECRETS_FD";
+
+/// 密文交给子进程的方式；明文取值始终通过环境变量传递。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SecDelivery {
+    /// 写入环境变量，子进程的 `/proc/<pid>/environ` 可见。
+    #[default]
+    Env,
+    /// 以 JSON 对象写入子进程标准输入。
+    Stdin,
+    /// 以 JSON 对象写入匿名管道，读端在子进程中为指定编号的描述符。
+    #[cfg(unix)]
+    Fd(i32),
+}
+
+/// 注入到子进程的一个变量，`Debug` 输出中密文被遮蔽。
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecEnvVar {
+    pub name: String,
+    pub value: String,
+    pub secret: bool,
+}
+
+impl Debug for SecEnvVar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = if self.secret {
+            SECRET_MASK
+        } else {
+            self.value.as_str()
+        };
+        f.debug_struct("SecEnvVar")
+            .field("name", &self.name)
+            .field("value", &value)
+            .field("secret", &self.secret)
+            .finish()
+    }
+}
+
+/// 把 `SecValueObj` 注入子进程。
+///
+/// 对象按 [`FlatStyle::Env`] 展开并加前缀（默认 `SEC_`，如 `SEC_DB__PASSWORD`）；
+/// 已带该前缀的键（如 `load_secfile_by` 返回的 `SEC_DB_PASS`）不再重复添加。
+/// 可用路径查询只注入部分取值。默认移除从父进程继承的同前缀变量，
+/// 避免子进程拿到未声明的旧密文。
+#[derive(Debug, Clone)]
+pub struct SecInjector {
+    prefix: String,
+    queries: Vec<SecQuery>,
+    clear_inherited: bool,
+    delivery: SecDelivery,
+}
+
+impl Default for SecInjector {
+    fn default() -> Self {
+        Self {
+            prefix: SEC_PREFIX.to_string(),
+            queries: Vec::new(),
+            clear_inherited: true,
+            delivery: SecDelivery::default(),
+        }
+    }
+}
+
+impl SecInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// 不加前缀；此时不会清理继承变量。
+    #[must_use]
+    pub fn without_prefix(mut self) -> Self {
+        self.prefix.clear();
+        self
+    }
+
+    /// 只注入命中任一查询的路径及其后代，可多次调用。
+    #[must_use]
+    pub fn with_query(mut self, query: SecQuery) -> Self {
+        self.queries.push(query);
+        self
+    }
+
+    #[must_use]
+    pub fn with_clear_inherited(mut self, clear: bool) -> Self {
+        self.clear_inherited = clear;
+        self
+    }
+
+    #[must_use]
+    pub fn with_delivery(mut self, delivery: SecDelivery) -> Self {
+        self.delivery = delivery;
+        self
+    }
+
+    /// 按展开顺序返回将要注入的变量，空容器被忽略。
+    ///
+    /// 键无法无歧义地展开，或加前缀后两个键得到同一变量名（不区分大小写）时返回错误。
+    pub fn vars(&self, obj: &SecValueObj) -> SecResult<Vec<SecEnvVar>> {
+        let selected: Vec<_> = self
+            .queries
+            .iter()
+            .flat_map(|q| q.select_paths(obj))
+            .collect();
+        let mut vars: Vec<SecEnvVar> = Vec::new();
+        for (key, value) in flatten(obj, FlatStyle::Env)? {
+            if matches!(value, SecValueType::Obj(_) | SecValueType::List(_)) {
+                continue;
+            }
+            let keep = selected.is_empty()
+                || FlatStyle::Env.parse(key.as_str()).is_ok_and(|path| {
+                    selected
+                        .iter()
+                        .any(|s| path.segments().starts_with(s.segments()))
+                });
+            if !keep {
+                continue;
+            }
+            let name = self.var_name(key.as_str());
+            if vars.iter().any(|v| v.name.eq_ignore_ascii_case(&name)) {
+                return OrionSecReason::from_validation()
+                    .to_err()
+                    .with_detail(format!(
+                        "{} maps to variable {name} more than once",
+                        key.as_str()
+                    ))
+                    .err();
+            }
+            vars.push(SecEnvVar {
+                name,
+                secret: value.is_secret(),
+                value: value.no_sec().to_string(),
+            });
+        }
+        Ok(vars)
+    }
+
+    fn var_name(&self, key: &str) -> String {
+        if key.to_uppercase().starts_with(&self.prefix.to_uppercase()) {
+            key.to_string()
+        } else {
+            format!("{}{key}", self.prefix)
+        }
+    }
+
+    /// 配置命令的环境与标准输入/描述符，返回需在启动后交付的密文。
+    ///
+    /// 一般直接使用 [`SecInjector::spawn`]；需要自行启动子进程时，
+    /// 在 `spawn` 之后调用 [`SecPayload::deliver`]。
+    pub fn apply(&self, cmd: &mut Command, obj: &SecValueObj) -> SecResult<SecPayload> {
+        if self.clear_inherited && !self.prefix.is_empty() {
+            for (key, _) in std::env::vars_os() {
+                if key
+                    .to_string_lossy()
+                    .to_uppercase()
+                    .starts_with(&self.prefix.to_uppercase())
+                {
+                    cmd.env_remove(key);
+                }
+            }
+        }
+        let mut secrets = serde_json::Map::new();
+        for var in self.vars(obj)? {
+            if var.secret && self.delivery != SecDelivery::Env {
+                secrets.insert(var.name, var.value.into());
+            } else {
+                cmd.env(var.name, var.value);
+            }
+        }
+        let data = if secrets.is_empty() {
+            Vec::new()
+        } else {
+            serde_json::to_vec(&secrets).owe_sys()?
+        };
+        let channel = match self.delivery {
+            SecDelivery::Env => Channel::None,
+            SecDelivery::Stdin => {
+                cmd.stdin(Stdio::piped());
+                Channel::Stdin
+            }
+            #[cfg(unix)]
+            SecDelivery::Fd(fd) => {
+                if fd <= 2 {
+                    return OrionSecReason::from_validation()
+                        .to_err()
+                        .with_detail(format!("secret fd must be above 2, got {fd}"))
+                        .err();
+                }
+                let (reader, writer) = std::io::pipe().owe_sys()?;
+                attach_fd(cmd, &reader, fd);
+                cmd.env(format!("{}{SECRETS_FD_VAR}", self.prefix), fd.to_string());
+                Channel::Pipe(reader, writer)
+            }
+        };
+        Ok(SecPayload { channel, data })
+    }
+
+    /// 配置并启动子进程，随后在当前线程交付密文。
+    ///
+    /// 需要同时读取子进程输出时，先启动读取再交付（见 `SecRunner`），否则可能互相阻塞。
+    pub fn spawn(&self, cmd: &mut Command, obj: &SecValueObj) -> SecResult<Child> {
+        let payload = self.apply(cmd, obj)?;
+        let mut child = cmd.spawn().owe_sys()?;
+        payload.deliver(&mut child)?;
+        Ok(child)
+    }
+}
+
+enum Channel {
+    None,
+    Stdin,
+    #[cfg(unix)]
+    Pipe(std::io::PipeReader, std::io::PipeWriter),
+}
+
+/// 待交付给子进程的密文，由 [`SecInjector::apply`] 返回。
+pub struct SecPayload {
+    channel: Channel,
+    data: Vec<u8>,
+}
+
+impl Debug for SecPayload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecPayload")
+            .field("len", &self.data.len())
+            .finish()
+    }
+}
+
+impl SecPayload {
+    /// 写出密文并关闭写端，子进程读到 EOF 即拿到完整 JSON。
+    ///
+    /// 写入在当前线程同步进行，密文超过管道缓冲区时需子进程及时读取。
+    /// 子进程不读取就关闭输入或退出（`BrokenPipe`）不视为错误，由退出状态反映结果。
+    pub fn deliver(self, child: &mut Child) -> SecResult<()> {
+        match self.channel {
+            Channel::None => Ok(()),
+            Channel::Stdin => match child.stdin.take() {
+                Some(mut stdin) => write_payload(&mut stdin, &self.data),
+                None => Ok(()),
+            },
+            #[cfg(unix)]
+            Channel::Pipe(reader, mut writer) => {
+                drop(reader);
+                write_payload(&mut writer, &self.data)
+            }
+        }
+    }
+}
+
+fn write_payload<W: Write>(writer: &mut W, data: &[u8]) -> SecResult<()> {
+    match writer.write_all(data) {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result.owe_sys(),
+    }
+}
+
+/// 在子进程中把管道读端复制到指定描述符。
+#[cfg(unix)]
+fn attach_fd(cmd: &mut Command, reader: &std::io::PipeReader, fd: i32) {
+    use std::os::{fd::AsRawFd, unix::process::CommandExt};
+    let raw = reader.as_raw_fd();
+    // SAFETY: fork 与 exec 之间只调用异步信号安全的 dup2/fcntl，不分配内存。
+    unsafe {
+        cmd.pre_exec(move || {
+            if raw == fd {
+                let flags = libc::fcntl(fd, libc::F_GETFD);
+                if flags < 0 || libc::fcntl(fd, libc::F_SETFD, flags & !libc::FD_CLOEXEC) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            } else if libc::dup2(raw, fd) < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::SecFrom;
+
+    fn sample() -> SecValueObj {
+        let mut db = SecValueObj::new();
+        db.insert("USER".into(), SecValueType::nor_from("app".to_string()));
+        db.insert(
+            "PASSWORD".into(),
+            SecValueType::sec_from("p@ss w0rd".to_string()),
+        );
+        db.insert("PORT".into(), SecValueType::nor_from(5432u64));
+        let mut obj = SecValueObj::new();
+        obj.insert("DB".into(), SecValueType::from(db));
+        obj.insert("TOKEN".into(), SecValueType::sec_from("tok".to_string()));
+        obj
+    }
+
+    fn output(cmd: &mut Command, injector: &SecInjector) -> String {
+        cmd.stdout(Stdio::piped());
+        let child = injector.spawn(cmd, &sample()).unwrap();
+        let out = child.wait_with_output().unwrap();
+        assert!(out.status.success());
+        String::from_utf8(out.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_inject_vars_filtered_and_masked() {
+        let injector = SecInjector::new().with_query(SecQuery::parse("db").unwrap());
+        let vars = injector.vars(&sample()).unwrap();
+        let names: Vec<_> = vars.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["SEC_DB__USER", "SEC_DB__PASSWORD", "SEC_DB__PORT"]
+        );
+        assert_eq!(vars[2].value, "5432");
+        assert!(vars[1].secret);
+        assert!(!format!("{vars:?}").contains("w0rd"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_inject_loaded_secfile_keeps_prefix_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sec_value.yml");
+        std::fs::write(&path, "db_pass: s3cret\nhost: db.local\n").unwrap();
+        let obj = crate::load::load_secfile_by(path, crate::load::SecFileFmt::Yaml).unwrap();
+        let injector = SecInjector::new();
+        let names: Vec<_> = injector
+            .vars(&obj)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.name)
+            .collect();
+        assert_eq!(names, vec!["SEC_DB_PASS", "SEC_HOST"]);
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(r#"printf '%s|%s' "${SEC_DB_PASS:-none}" "${SEC_SEC_DB_PASS:-none}""#)
+            .stdout(Stdio::piped());
+        let out = injector
+            .spawn(&mut cmd, &obj)
+            .unwrap()
+            .wait_with_output()
+            .unwrap();
+        assert_eq!(String::from_utf8(out.stdout).unwrap(), "s3cret|none");
+    }
+
+    #[test]
+    fn test_inject_vars_duplicate_name() {
+        let mut obj = SecValueObj::new();
+        obj.insert("SEC_TOKEN".into(), SecValueType::sec_from("a".to_string()));
+        obj.insert("TOKEN".into(), SecValueType::nor_from("b".to_string()));
+        let err = SecInjector::new().vars(&obj).unwrap_err();
+        assert!(format!("{err}").contains("TOKEN maps to variable SEC_TOKEN"));
+
+        let mut a = SecValueObj::new();
+        a.insert(
+            "B".into(),
+            SecValueType::sec_from("nested-secret".to_string()),
+        );
+        let mut obj = SecValueObj::new();
+        obj.insert("A".into(), SecValueType::from(a));
+        obj.insert(
+            "A__B".into(),
+            SecValueType::nor_from("top-public".to_string()),
+        );
+        assert!(SecInjector::new().vars(&obj).is_err());
+        assert!(
+            SecInjector::new()
+                .apply(&mut Command::new("true"), &obj)
+                .is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_inject_clears_inherited_prefix_case_insensitive() {
+        let _guard = crate::env::env_lock();
+        unsafe { std::env::set_var("sec_inject_stale", "old") };
+        let out = output(
+            Command::new("sh")
+                .arg("-c")
+                .arg(r#"printf '%s' "${sec_inject_stale:-none}""#),
+            &SecInjector::new(),
+        );
+        unsafe { std::env::remove_var("sec_inject_stale") };
+        assert_eq!(out, "none");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_inject_env_and_fd_delivery() {
+        let script = r#"printf '%s|%s|%s|' "$SEC_DB__USER" "${SEC_DB__PASSWORD:-none}" "${SEC_SECRETS_FD:-}""#;
+        let out = output(
+            Command::new("sh").arg("-c").arg(script),
+            &SecInjector::new(),
+        );
+        assert_eq!(out, "app|p@ss w0rd||");
+
+        let script = format!(r#"{script}; cat <&3"#);
+        let out = output(
+            Command::new("sh").arg("-c").arg(script),
+            &SecInjector::new().with_delivery(SecDelivery::Fd(3)),
+        );
+        assert_eq!(
+            out,
+            r#"app|none|3|{"SEC_DB__PASSWORD":"p@ss w0rd","SEC_TOKEN":"tok"}"#
+        );
+
+        let out = output(
+            Command::new("sh")
+                .arg("-c")
+                .arg("printf '%s|' \"${SEC_TOKEN:-none}\"; cat"),
+            &SecInjector::new().with_delivery(SecDelivery::Stdin),
+        );
+        assert_eq!(
+            out,
+            r#"none|{"SEC_DB__PASSWORD":"p@ss w0rd","SEC_TOKEN":"tok"}"#
+        );
+    }
+}
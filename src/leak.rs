@@ -0,0 +1,249 @@
+use std::fmt::{Display, Formatter};
+
+use data_encoding::{
+    BASE64, BASE64_NOPAD, BASE64URL, BASE64URL_NOPAD, Encoding, HEXLOWER_PERMISSIVE,
+};
+use orion_error::{ToStructError, UvsFrom};
+use orion_variate::vars::ValueDict;
+
+use crate::{
+    audit::SecretScalars,
+    error::{OrionSecReason, SecResult},
+    query::SecPath,
+    scan::Strings,
+    sec::{SecFrom, SecValueObj, SecValueType},
+    visit::visit,
+};
+
+/// 密文在明文配置中出现的形式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeakEncoding {
+    Plain,
+    /// URL 百分号编码。
+    Url,
+    Base64,
+    Hex,
+}
+
+impl Display for LeakEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            LeakEncoding::Plain => "plain",
+            LeakEncoding::Url => "url",
+            LeakEncoding::Base64 => "base64",
+            LeakEncoding::Hex => "hex",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 明文配置中 `public_path` 的取值包含了 `secret_path` 的密文。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecLeak {
+    pub public_path: SecPath,
+    pub secret_path: SecPath,
+    pub encoding: LeakEncoding,
+}
+
+impl Display for SecLeak {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} contains secret {} ({})",
+            self.public_path, self.secret_path, self.encoding
+        )
+    }
+}
+
+/// 交叉检查明文配置与密文：找出被原样或编码后复制到明文配置中的密文。
+///
+/// 明文取值先按原样比较，再尝试 URL 解码以及对其中 base64/hex 片段解码，
+/// 因此 `postgres://u:pw@db`、`Basic dXNlcjpwdw==` 这类嵌入形式也能发现。
+/// 短于 `min_length` 的密文不参与比较，以免 `true`、端口号之类的值误报。
+#[derive(Debug, Clone)]
+pub struct SecLeakChecker {
+    min_length: usize,
+}
+
+impl Default for SecLeakChecker {
+    fn default() -> Self {
+        Self { min_length: 6 }
+    }
+}
+
+impl SecLeakChecker {
+    #[must_use]
+    pub fn with_min_length(mut self, len: usize) -> Self {
+        self.min_length = len;
+        self
+    }
+
+    /// 按明文配置的遍历顺序返回全部泄露，同一对路径只报告一次。
+    pub fn find(&self, public: &ValueDict, secrets: &SecValueObj) -> Vec<SecLeak> {
+        let mut collect = SecretScalars::default();
+        visit(secrets, &mut collect);
+        let secret_values: Vec<_> = collect
+            .found
+            .into_iter()
+            .filter(|(_, v)| v.chars().count() >= self.min_length)
+            .collect();
+        if secret_values.is_empty() {
+            return Vec::new();
+        }
+
+        let public_obj: SecValueObj = public
+            .iter()
+            .map(|(k, v)| (k.clone(), SecValueType::nor_from(v.clone())))
+            .collect();
+        let mut strings = Strings::default();
+        visit(&public_obj, &mut strings);
+
+        let mut leaks = Vec::new();
+        for (public_path, value) in strings.found {
+            let views = decoded_views(&value);
+            for (secret_path, secret) in &secret_values {
+                if let Some((encoding, _)) = views.iter().find(|(_, v)| v.contains(secret.as_str()))
+                {
+                    leaks.push(SecLeak {
+                        public_path: public_path.clone(),
+                        secret_path: secret_path.clone(),
+                        encoding: *encoding,
+                    });
+                }
+            }
+        }
+        leaks
+    }
+
+    /// 存在泄露时返回错误，错误中只列出路径与编码形式。
+    pub fn check(&self, public: &ValueDict, secrets: &SecValueObj) -> SecResult<()> {
+        let leaks = self.find(public, secrets);
+        if leaks.is_empty() {
+            return Ok(());
+        }
+        let detail = leaks
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        OrionSecReason::from_validation()
+            .to_err()
+            .with_detail(format!("secrets leaked into public config: {detail}"))
+            .err()
+    }
+}
+
+/// 明文取值本身及其可解码形式。
+fn decoded_views(value: &str) -> Vec<(LeakEncoding, String)> {
+    let mut views = vec![(LeakEncoding::Plain, value.to_string())];
+    if value.contains('%')
+        && let Some(decoded) = percent_decode(value)
+    {
+        views.push((LeakEncoding::Url, decoded));
+    }
+    for token in value
+        .split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '_' | '-')))
+        .filter(|t| t.len() >= 8)
+    {
+        if token.len() % 2 == 0
+            && token.chars().all(|c| c.is_ascii_hexdigit())
+            && let Some(decoded) = decode(&HEXLOWER_PERMISSIVE, token)
+        {
+            views.push((LeakEncoding::Hex, decoded));
+        }
+        if let Some(decoded) = [&BASE64, &BASE64_NOPAD, &BASE64URL, &BASE64URL_NOPAD]
+            .into_iter()
+            .find_map(|enc| decode(enc, token))
+        {
+            views.push((LeakEncoding::Base64, decoded));
+        }
+    }
+    views
+}
+
+fn decode(enc: &Encoding, token: &str) -> Option<String> {
+    enc.decode(token.as_bytes())
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(b) = escaped {
+            out.push(b);
+            i += 3;
+            continue;
+        }
+        out.push(if bytes[i] == b'+' { b' ' } else { bytes[i] });
+        i += 1;
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use orion_variate::vars::ValueType;
+
+    fn secrets() -> SecValueObj {
+        let mut db = SecValueObj::new();
+        db.insert(
+            "PASSWORD".into(),
+            SecValueType::sec_from("p@ss w0rd!".to_string()),
+        );
+        db.insert("USER".into(), SecValueType::nor_from("app".to_string()));
+        let mut obj = SecValueObj::new();
+        obj.insert("DB".into(), SecValueType::from(db));
+        obj.insert(
+            "API_TOKEN".into(),
+            SecValueType::sec_from("tok-123456".to_string()),
+        );
+        obj.insert("PIN".into(), SecValueType::sec_from("1234".to_string()));
+        obj
+    }
+
+    #[test]
+    fn test_leak_detects_plain_and_encoded() {
+        let hex = data_encoding::HEXLOWER.encode(b"tok-123456");
+        let basic = BASE64.encode(b"app:p@ss w0rd!");
+        let mut public = ValueDict::new();
+        public.insert("name", ValueType::from("demo"));
+        public.insert(
+            "dsn",
+            ValueType::from("postgres://app:p%40ss+w0rd%21@db:5432/app"),
+        );
+        public.insert("auth", ValueType::from(format!("Basic {basic}")));
+        public.insert("token", ValueType::from("tok-123456"));
+        public.insert("hint", ValueType::from(hex));
+        public.insert("port", ValueType::from("1234"));
+
+        let leaks = SecLeakChecker::default().find(&public, &secrets());
+        let text: Vec<String> = leaks.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            text,
+            vec![
+                "DSN contains secret DB.PASSWORD (url)",
+                "AUTH contains secret DB.PASSWORD (base64)",
+                "TOKEN contains secret API_TOKEN (plain)",
+                "HINT contains secret API_TOKEN (hex)",
+            ]
+        );
+        let err = SecLeakChecker::default()
+            .check(&public, &secrets())
+            .unwrap_err()
+            .to_string();
+        assert!(!err.contains("tok-123456") && !err.contains("w0rd"));
+
+        let mut clean = ValueDict::new();
+        clean.insert("name", ValueType::from("demo"));
+        assert!(SecLeakChecker::default().check(&clean, &secrets()).is_ok());
+    }
+}
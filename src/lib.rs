@@ -1,8 +1,37 @@
+pub mod audit;
+pub mod classify;
+mod dict;
+pub mod diff;
+mod env;
 mod error;
+pub mod export;
+pub mod flat;
+mod fsio;
+pub mod generate;
+pub mod inject;
+pub mod interp;
+pub mod leak;
 mod load;
+pub mod meta;
+mod patch;
+pub mod policy;
+pub mod query;
+mod random;
+pub mod resolve;
+pub mod rotate;
+pub mod scan;
+pub mod scrub;
 pub mod sec;
+pub mod snapshot;
+pub mod store;
 pub mod types;
+pub mod visit;
+pub mod watch;
+pub use dict::{SecEnvDict, load_sec_env_dict, load_sec_env_dict_by};
+pub use env::{ENV_NEST_SEP, load_sec_env, load_sec_env_by, parse_env_value, sec_obj_from_vars};
 pub use error::{OrionSecReason, SecError, SecReason, SecResult};
 pub use load::{
-    SecFileFmt, load_galaxy_secfile, load_sec_dict, load_sec_dict_by, load_secfile, load_secfile_by,
+    KeyCase, SecFileFmt, SecLoadOptions, load_galaxy_secfile, load_sec_dict, load_sec_dict_by,
+    load_sec_dir_by, load_secfile, load_secfile_by, load_secfile_named_with,
+    load_secfile_resolved_with, load_secfile_with, save_secfile_by,
 };
@@ -1,19 +1,26 @@
-use std::{env, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
+use indexmap::IndexMap;
 use log::{info, warn};
-use orion_conf::{TomlIO, YamlIO};
-use orion_error::{ErrorOwe, ErrorWith};
+use orion_conf::{JsonIO, TomlIO, YamlIO};
+use orion_error::{ErrorOwe, ErrorWith, ToStructError, UvsFrom};
 use orion_variate::vars::UpperKey;
-use orion_variate::vars::{EnvDict, ValueDict};
+use orion_variate::vars::{EnvDict, ValueDict, ValueType};
 
 use crate::{
-    error::SecResult,
-    sec::{NoSecConv, SecFrom, SecValueObj, SecValueType},
+    error::{OrionSecReason, SecResult},
+    fsio::write_private,
+    meta::{META_KEY, META_VALUE_KEY, SecMeta},
+    resolve::SecResolverSet,
+    sec::{NoSecConv, SecFrom, SecList, SecObj, SecValueObj, SecValueType},
 };
 
-const SEC_PREFIX: &str = "SEC_";
+pub(crate) const SEC_PREFIX: &str = "SEC_";
 const SEC_VALUE_FILE_NAME: &str = "sec_value.yml";
-const GALAXY_DOT_DIR: &str = ".galaxy";
+pub(crate) const GALAXY_DOT_DIR: &str = ".galaxy";
 const DEFAULT_FALLBACK_DIR: &str = "./";
 
 pub fn load_sec_dict() -> SecResult<EnvDict> {
@@ -44,13 +51,256 @@ pub fn load_galaxy_secfile() -> SecResult<SecValueObj> {
     let default = sec_value_galaxy_path();
     load_secfile_by(default, SecFileFmt::Yaml)
 }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SecFileFmt {
     Yaml,
     Toml,
+    Json,
+    /// Kubernetes / Docker Swarm 挂载的密文目录：每个文件一个键，文件名为键，去除首尾空白的内容为值。
+    ///
+    /// 以 `.` 开头的条目（如 `..data` 及其指向的时间戳目录）被忽略，其余条目跟随符号链接读取。
+    Dir,
+}
+
+/// 加载后键名的大小写策略。
+///
+/// 只作用于 [`load_secfile_named_with`] 返回的字符串键；
+/// `load_secfile_with` 等返回的 `SecValueObj` 以大小写不敏感的 `UpperKey` 为键，始终按大写存储，不受此策略影响。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyCase {
+    #[default]
+    Upper,
+    Preserve,
+    Lower,
+}
+
+impl KeyCase {
+    pub fn apply(&self, key: &str) -> String {
+        match self {
+            KeyCase::Upper => key.to_uppercase(),
+            KeyCase::Preserve => key.to_string(),
+            KeyCase::Lower => key.to_lowercase(),
+        }
+    }
+}
+
+/// 控制密文文件加载后键名的生成方式。
+///
+/// 默认值与 `load_secfile_by` 一致：顶层键加 `SEC_` 前缀并转为大写，嵌套对象保持原结构。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecLoadOptions {
+    prefix: Option<String>,
+    key_case: KeyCase,
+    flatten: bool,
+    separator: String,
+    resolve: bool,
+}
+
+impl Default for SecLoadOptions {
+    fn default() -> Self {
+        Self {
+            prefix: Some(SEC_PREFIX.to_string()),
+            key_case: KeyCase::Upper,
+            flatten: false,
+            separator: "_".to_string(),
+            resolve: false,
+        }
+    }
+}
+
+impl SecLoadOptions {
+    #[must_use]
+    pub fn with_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+    #[must_use]
+    pub fn without_prefix(mut self) -> Self {
+        self.prefix = None;
+        self
+    }
+    /// 见 [`KeyCase`]：仅影响 `load_secfile_named_with` 的结果。
+    #[must_use]
+    pub fn with_key_case(mut self, key_case: KeyCase) -> Self {
+        self.key_case = key_case;
+        self
+    }
+    /// 将嵌套对象与数组展开为带前缀的顶层键，如 `db.password` → `SEC_DB_PASSWORD`。
+    #[must_use]
+    pub fn with_flatten(mut self, flatten: bool) -> Self {
+        self.flatten = flatten;
+        self
+    }
+    /// 展开嵌套键时使用的连接符，默认为 `_`。
+    #[must_use]
+    pub fn with_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+    /// 是否在加载时用 [`SecResolverSet::default`]（`file://`、`env://`）解析引用，默认关闭。
+    /// 需要 `exec://` 时使用 [`load_secfile_resolved_with`] 并显式注册 `ExecResolver`。
+    #[must_use]
+    pub fn with_resolve(mut self, resolve: bool) -> Self {
+        self.resolve = resolve;
+        self
+    }
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+    pub fn key_case(&self) -> KeyCase {
+        self.key_case
+    }
+    pub fn flatten(&self) -> bool {
+        self.flatten
+    }
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+    pub fn resolve(&self) -> bool {
+        self.resolve
+    }
+
+    fn key_name(&self, key: &str) -> String {
+        let prefix = self.prefix.as_deref().unwrap_or_default();
+        self.key_case.apply(&format!("{prefix}{key}"))
+    }
+
+    /// `source` 为该取值在文件中的路径（如 `db.pass`），用于报告展开后的键名冲突。
+    fn collect(
+        &self,
+        name: String,
+        source: String,
+        value: ValueType,
+        out: &mut IndexMap<String, SecValueType>,
+        sources: &mut IndexMap<String, String>,
+    ) -> SecResult<()> {
+        if self.flatten && split_meta(&value)?.is_none() {
+            match value {
+                ValueType::Obj(obj) if !obj.is_empty() => {
+                    for (k, v) in obj {
+                        let child = self.key_case.apply(&k);
+                        self.collect(
+                            format!("{name}{}{child}", self.separator),
+                            format!("{source}.{k}"),
+                            v,
+                            out,
+                            sources,
+                        )?;
+                    }
+                    return Ok(());
+                }
+                ValueType::List(list) if !list.is_empty() => {
+                    for (i, v) in list.into_iter().enumerate() {
+                        self.collect(
+                            format!("{name}{}{i}", self.separator),
+                            format!("{source}[{i}]"),
+                            v,
+                            out,
+                            sources,
+                        )?;
+                    }
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+        if let Some(first) = sources.insert(name.to_uppercase(), source.clone()) {
+            return OrionSecReason::from_validation()
+                .to_err()
+                .with_detail(format!("`{first}` and `{source}` both map to key `{name}`"))
+                .err();
+        }
+        out.insert(name, sec_value(value)?);
+        Ok(())
+    }
+}
+
+/// 识别 `{ value: ..., _meta: {...} }` 形式的取值，返回内部取值与元数据。
+fn split_meta(value: &ValueType) -> SecResult<Option<(ValueType, SecMeta)>> {
+    let ValueType::Obj(obj) = value else {
+        return Ok(None);
+    };
+    let find = |name: &str| obj.iter().find(|(k, _)| k.eq_ignore_ascii_case(name));
+    let Some((_, raw_meta)) = find(META_KEY) else {
+        return Ok(None);
+    };
+    match find(META_VALUE_KEY) {
+        Some((_, inner)) if obj.len() == 2 => {
+            Ok(Some((inner.clone(), SecMeta::from_value(raw_meta)?)))
+        }
+        _ => OrionSecReason::from_validation()
+            .to_err()
+            .with_detail(format!(
+                "`{META_KEY}` must be paired with a single `{META_VALUE_KEY}`"
+            ))
+            .err(),
+    }
+}
+
+/// 与 `SecValueType::sec_from` 相同，但会展开各层的 `_meta` 包装。
+fn sec_value(value: ValueType) -> SecResult<SecValueType> {
+    if let Some((inner, meta)) = split_meta(&value)? {
+        let mut converted = sec_value(inner)?;
+        converted.set_meta(Some(meta));
+        return Ok(converted);
+    }
+    Ok(match value {
+        ValueType::Obj(obj) => SecValueType::Obj(SecObj::sec_from(
+            obj.into_iter()
+                .map(|(k, v)| Ok((UpperKey::from(k), sec_value(v)?)))
+                .collect::<SecResult<SecValueObj>>()?,
+        )),
+        ValueType::List(list) => SecValueType::List(SecList::sec_from(
+            list.into_iter().map(sec_value).collect::<SecResult<_>>()?,
+        )),
+        other => SecValueType::sec_from(other),
+    })
 }
 
+/// 加载密文文件，取值原样保留；解析引用见 [`SecLoadOptions::with_resolve`]。
 pub fn load_secfile_by(sec_file: PathBuf, fmt: SecFileFmt) -> SecResult<SecValueObj> {
-    let mut vars_dict = SecValueObj::new();
+    load_secfile_with(sec_file, fmt, &SecLoadOptions::default())
+}
+
+pub fn load_secfile_with(
+    sec_file: PathBuf,
+    fmt: SecFileFmt,
+    opts: &SecLoadOptions,
+) -> SecResult<SecValueObj> {
+    let resolvers = SecResolverSet::default();
+    load_secfile_resolved_with(sec_file, fmt, opts, &resolvers)
+}
+
+/// 与 [`load_secfile_with`] 相同，但使用自定义的引用解析器。
+pub fn load_secfile_resolved_with(
+    sec_file: PathBuf,
+    fmt: SecFileFmt,
+    opts: &SecLoadOptions,
+    resolvers: &SecResolverSet,
+) -> SecResult<SecValueObj> {
+    let named = load_named(sec_file, fmt, opts, resolvers)?;
+    Ok(named
+        .into_iter()
+        .map(|(k, v)| (UpperKey::from(k), v))
+        .collect())
+}
+
+/// 与 [`load_secfile_with`] 相同，但以字符串保存键名，从而保留 [`KeyCase`] 的大小写。
+pub fn load_secfile_named_with(
+    sec_file: PathBuf,
+    fmt: SecFileFmt,
+    opts: &SecLoadOptions,
+) -> SecResult<IndexMap<String, SecValueType>> {
+    load_named(sec_file, fmt, opts, &SecResolverSet::default())
+}
+
+fn load_named(
+    sec_file: PathBuf,
+    fmt: SecFileFmt,
+    opts: &SecLoadOptions,
+    resolvers: &SecResolverSet,
+) -> SecResult<IndexMap<String, SecValueType>> {
+    let mut vars_dict = IndexMap::new();
     if sec_file.exists() {
         let dict = match fmt {
             SecFileFmt::Yaml => ValueDict::load_yaml(&sec_file)
@@ -59,18 +309,122 @@ pub fn load_secfile_by(sec_file: PathBuf, fmt: SecFileFmt) -> SecResult<SecValue
             SecFileFmt::Toml => ValueDict::load_toml(&sec_file)
                 .owe_logic()
                 .with(&sec_file)?,
+            SecFileFmt::Json => ValueDict::load_json(&sec_file)
+                .owe_logic()
+                .with(&sec_file)?,
+            SecFileFmt::Dir => load_dir_dict(&sec_file)?,
         };
         info!(target: "exec","  load {}", sec_file.display());
+        let mut sources = IndexMap::new();
         for (k, v) in dict.iter() {
-            vars_dict.insert(
-                UpperKey::from(format!("{}{}", SEC_PREFIX, k.as_str().to_uppercase())),
-                SecValueType::sec_from(v.clone()),
-            );
+            opts.collect(
+                opts.key_name(k.as_str()),
+                k.as_str().to_string(),
+                v.clone(),
+                &mut vars_dict,
+                &mut sources,
+            )
+            .with(&sec_file)?;
+        }
+        if opts.resolve {
+            for (k, v) in vars_dict.iter_mut() {
+                resolvers.apply_value(k, v).with(&sec_file)?;
+            }
         }
     }
     Ok(vars_dict)
 }
 
+/// 读取 `dot_path(dot_name)/dir_name` 目录形式的密文。
+pub fn load_sec_dir_by(dot_name: &str, dir_name: &str) -> SecResult<SecValueObj> {
+    load_secfile_by(dot_path(dot_name).join(dir_name), SecFileFmt::Dir)
+}
+
+/// 将 `SecValueObj` 写回密文文件，带元数据的节点写作 `{ value, _meta }`。
+///
+/// 以临时文件（0600）写入后原子替换目标；键名按 `SecValueObj` 的大写形式写出，
+/// 调用方应传入未加前缀（`SecLoadOptions::without_prefix`）加载的对象。
+pub fn save_secfile_by(sec_file: &Path, obj: &SecValueObj, fmt: SecFileFmt) -> SecResult<()> {
+    let dict: IndexMap<String, ValueType> = obj
+        .iter()
+        .map(|(k, v)| (k.as_str().to_string(), file_value(v)))
+        .collect();
+    let content = match fmt {
+        SecFileFmt::Yaml => serde_yaml::to_string(&dict).owe_data().with(sec_file)?,
+        SecFileFmt::Toml => toml::to_string(&dict).owe_data().with(sec_file)?,
+        SecFileFmt::Json => serde_json::to_string_pretty(&dict)
+            .owe_data()
+            .with(sec_file)?,
+        SecFileFmt::Dir => {
+            return OrionSecReason::from_validation()
+                .to_err()
+                .with_detail("secret directories are read-only")
+                .with(sec_file)
+                .err();
+        }
+    };
+    write_private(sec_file, content.as_bytes())?;
+    info!(target: "exec", "  save {}", sec_file.display());
+    Ok(())
+}
+
+/// [`sec_value`] 的逆操作。
+pub(crate) fn file_value(value: &SecValueType) -> ValueType {
+    let plain = match value {
+        SecValueType::Obj(obj) => ValueType::Obj(
+            obj.value()
+                .iter()
+                .map(|(k, v)| (k.as_str().to_string(), file_value(v)))
+                .collect(),
+        ),
+        SecValueType::List(list) => ValueType::List(list.value().iter().map(file_value).collect()),
+        other => other.clone().no_sec(),
+    };
+    match value.meta() {
+        Some(meta) => {
+            let mut wrapped = IndexMap::new();
+            wrapped.insert(META_VALUE_KEY.to_string(), plain);
+            wrapped.insert(META_KEY.to_string(), meta.to_value());
+            ValueType::Obj(wrapped)
+        }
+        None => plain,
+    }
+}
+
+fn load_dir_dict(dir: &Path) -> SecResult<ValueDict> {
+    let mut entries = fs::read_dir(dir)
+        .owe_sys()
+        .with(dir)?
+        .collect::<Result<Vec<_>, _>>()
+        .owe_sys()
+        .with(dir)?;
+    entries.sort_by_key(|e| e.file_name());
+
+    let mut dict = ValueDict::new();
+    for entry in entries {
+        let Ok(name) = entry.file_name().into_string() else {
+            warn!(target: "exec", "  skip non utf-8 file name in {}", dir.display());
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        // fs::metadata 跟随符号链接，`key -> ..data/key` 会解析到真实文件
+        if !fs::metadata(&path).owe_sys().with(&path)?.is_file() {
+            continue;
+        }
+        let bytes = fs::read(&path).owe_sys().with(&path)?;
+        match String::from_utf8(bytes) {
+            Ok(content) => {
+                dict.insert(name, ValueType::String(content.trim().to_string()));
+            }
+            Err(_) => warn!(target: "exec", "  skip non utf-8 secret {}", path.display()),
+        }
+    }
+    Ok(dict)
+}
+
 pub fn sec_value_galaxy_path() -> PathBuf {
     dot_path(GALAXY_DOT_DIR).join(SEC_VALUE_FILE_NAME)
 }
@@ -191,6 +545,256 @@ mod tests {
         assert!(result.unwrap().is_empty());
     }
 
+    fn nested_yaml() -> NamedTempFile {
+        let mut file = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(file, "dbConf:").unwrap();
+        writeln!(file, "  userName: root").unwrap();
+        writeln!(file, "  password: p").unwrap();
+        writeln!(file, "hosts:").unwrap();
+        writeln!(file, "  - a").unwrap();
+        writeln!(file, "  - b").unwrap();
+        writeln!(file, "token: t").unwrap();
+        file
+    }
+
+    #[test]
+    fn test_load_secfile_with_default_matches_by() {
+        let file = nested_yaml();
+        let path = file.path().to_path_buf();
+        let by = load_secfile_by(path.clone(), SecFileFmt::Yaml).unwrap();
+        let with = load_secfile_with(path, SecFileFmt::Yaml, &SecLoadOptions::default()).unwrap();
+        assert_eq!(by, with);
+        assert!(with.contains_key("SEC_DBCONF"));
+    }
+
+    #[test]
+    fn test_load_secfile_with_custom_prefix_and_flatten() {
+        let file = nested_yaml();
+        let opts = SecLoadOptions::default()
+            .with_prefix("APP_")
+            .with_flatten(true);
+        let obj = load_secfile_with(file.path().to_path_buf(), SecFileFmt::Yaml, &opts).unwrap();
+        let keys: Vec<&str> = obj.keys().map(|k| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "APP_DBCONF_USERNAME",
+                "APP_DBCONF_PASSWORD",
+                "APP_HOSTS_0",
+                "APP_HOSTS_1",
+                "APP_TOKEN"
+            ]
+        );
+        assert!(obj.values().all(SecValueType::is_secret));
+    }
+
+    #[test]
+    fn test_load_secfile_with_no_prefix() {
+        let file = nested_yaml();
+        let opts = SecLoadOptions::default().without_prefix();
+        let obj = load_secfile_with(file.path().to_path_buf(), SecFileFmt::Yaml, &opts).unwrap();
+        assert!(obj.contains_key("TOKEN"));
+        assert!(obj.contains_key("DBCONF"));
+        assert!(!obj.contains_key("SEC_TOKEN"));
+    }
+
+    #[test]
+    fn test_load_secfile_named_with_key_case() {
+        let file = nested_yaml();
+        let path = file.path().to_path_buf();
+
+        let preserve = SecLoadOptions::default()
+            .with_prefix("sec.")
+            .with_key_case(KeyCase::Preserve)
+            .with_flatten(true)
+            .with_separator(".");
+        let named = load_secfile_named_with(path.clone(), SecFileFmt::Yaml, &preserve).unwrap();
+        assert!(named.contains_key("sec.dbConf.userName"));
+        assert!(named.contains_key("sec.hosts.1"));
+
+        let lower = SecLoadOptions::default().with_key_case(KeyCase::Lower);
+        let named = load_secfile_named_with(path, SecFileFmt::Yaml, &lower).unwrap();
+        let keys: Vec<&String> = named.keys().collect();
+        assert_eq!(keys, vec!["sec_dbconf", "sec_hosts", "sec_token"]);
+    }
+
+    #[test]
+    fn test_load_secfile_by_resolves_references() {
+        let mut target = NamedTempFile::new().unwrap();
+        write!(target, "s3cr3t").unwrap();
+        let mut file = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(file, "db_pass: file://{}", target.path().display()).unwrap();
+        writeln!(file, "plain: https://example.com").unwrap();
+
+        let opts = SecLoadOptions::default().with_resolve(true);
+        let result = load_secfile_with(file.path().to_path_buf(), SecFileFmt::Yaml, &opts).unwrap();
+        assert_eq!(
+            result.get("SEC_DB_PASS"),
+            Some(&SecValueType::sec_from("s3cr3t".to_string()))
+        );
+
+        let raw = load_secfile_by(file.path().to_path_buf(), SecFileFmt::Yaml).unwrap();
+        assert_eq!(
+            raw.get("SEC_DB_PASS"),
+            Some(&SecValueType::sec_from(format!(
+                "file://{}",
+                target.path().display()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_load_secfile_by_meta_wrapper() {
+        let mut file = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(file, "api_token:").unwrap();
+        writeln!(file, "  value: t0k3n").unwrap();
+        writeln!(file, "  _meta:").unwrap();
+        writeln!(file, "    expires_at: \"2026-12-01T00:00:00Z\"").unwrap();
+        writeln!(file, "    rotation: 30d").unwrap();
+        writeln!(file, "db:").unwrap();
+        writeln!(file, "  pass:").unwrap();
+        writeln!(file, "    value: p").unwrap();
+        writeln!(file, "    _meta: {{ not_before: \"2026-01-01\" }}").unwrap();
+
+        let opts = SecLoadOptions::default().with_flatten(true);
+        let result = load_secfile_with(file.path().to_path_buf(), SecFileFmt::Yaml, &opts).unwrap();
+        let token = result.get("SEC_API_TOKEN").unwrap();
+        assert_eq!(token.to_string(), "********");
+        let meta = token.meta().unwrap();
+        assert!(meta.expires_at.is_some());
+        assert_eq!(meta.rotation, Some(chrono::Duration::days(30)));
+        assert!(result.get("SEC_DB_PASS").unwrap().meta().is_some());
+
+        let nested = load_secfile_by(file.path().to_path_buf(), SecFileFmt::Yaml).unwrap();
+        let SecValueType::Obj(db) = nested.get("SEC_DB").unwrap() else {
+            panic!("expected obj");
+        };
+        let pass = db.value().get("PASS").unwrap();
+        assert_eq!(pass, &{
+            let mut v = SecValueType::sec_from("p".to_string());
+            v.set_meta(pass.meta().cloned());
+            v
+        });
+        assert!(pass.meta().unwrap().not_before.is_some());
+    }
+
+    #[test]
+    fn test_load_secfile_flatten_collision() {
+        let mut file = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(file, "db_pass: a").unwrap();
+        writeln!(file, "db:").unwrap();
+        writeln!(file, "  pass: b").unwrap();
+        let opts = SecLoadOptions::default().with_flatten(true);
+        let err = load_secfile_with(file.path().to_path_buf(), SecFileFmt::Yaml, &opts)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`db_pass` and `db.pass`"), "{err}");
+        assert!(!err.contains("`a`") && !err.contains("`b`"));
+    }
+
+    #[test]
+    fn test_load_secfile_by_invalid_meta() {
+        let mut file = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(file, "token:").unwrap();
+        writeln!(file, "  value: t").unwrap();
+        writeln!(file, "  extra: x").unwrap();
+        writeln!(file, "  _meta: {{ rotation: 1y }}").unwrap();
+        assert!(load_secfile_by(file.path().to_path_buf(), SecFileFmt::Yaml).is_err());
+
+        let mut file = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(file, "token:").unwrap();
+        writeln!(file, "  value: t").unwrap();
+        writeln!(file, "  _meta: {{ rotation: 99999999999999d }}").unwrap();
+        assert!(load_secfile_by(file.path().to_path_buf(), SecFileFmt::Yaml).is_err());
+    }
+
+    #[test]
+    fn test_save_secfile_by_round_trip() {
+        let mut src = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(src, "token:").unwrap();
+        writeln!(src, "  value: t").unwrap();
+        writeln!(
+            src,
+            "  _meta: {{ created_at: \"2026-01-01\", rotation: 30d }}"
+        )
+        .unwrap();
+        writeln!(src, "db:").unwrap();
+        writeln!(src, "  hosts: [a, b]").unwrap();
+        writeln!(src, "  port: 5432").unwrap();
+        let opts = SecLoadOptions::default().without_prefix();
+        let obj = load_secfile_with(src.path().to_path_buf(), SecFileFmt::Yaml, &opts).unwrap();
+
+        let dir = TempDir::new().unwrap();
+        for (fmt, name) in [
+            (SecFileFmt::Yaml, "sec.yml"),
+            (SecFileFmt::Toml, "sec.toml"),
+            (SecFileFmt::Json, "sec.json"),
+        ] {
+            let path = dir.path().join(name);
+            save_secfile_by(&path, &obj, fmt).unwrap();
+            let reloaded = load_secfile_with(path, fmt, &opts).unwrap();
+            assert_eq!(reloaded, obj);
+            assert!(reloaded.get("TOKEN").unwrap().meta().is_some());
+        }
+        assert!(save_secfile_by(dir.path(), &obj, SecFileFmt::Dir).is_err());
+    }
+
+    #[test]
+    fn test_load_secfile_by_unresolved_reference() {
+        let mut file = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(file, "db_pass: env://ORION_SEC_LOAD_MISSING_VAR").unwrap();
+        let opts = SecLoadOptions::default().with_resolve(true);
+        let result = load_secfile_with(file.path().to_path_buf(), SecFileFmt::Yaml, &opts);
+        assert!(result.is_err());
+        assert!(load_secfile_by(file.path().to_path_buf(), SecFileFmt::Yaml).is_ok());
+    }
+
+    #[cfg(unix)]
+    fn k8s_secret_dir() -> TempDir {
+        use std::os::unix::fs::symlink;
+        let dir = TempDir::new().unwrap();
+        let stamp = dir.path().join("..2026_10_18_00_00_00.000");
+        fs::create_dir(&stamp).unwrap();
+        fs::write(stamp.join("db-password"), "s3cr3t\n").unwrap();
+        fs::write(stamp.join("token"), "  abc  ").unwrap();
+        symlink("..2026_10_18_00_00_00.000", dir.path().join("..data")).unwrap();
+        symlink("..data/db-password", dir.path().join("db-password")).unwrap();
+        symlink("..data/token", dir.path().join("token")).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_secfile_by_k8s_dir() {
+        let dir = k8s_secret_dir();
+        let result = load_secfile_by(dir.path().to_path_buf(), SecFileFmt::Dir).unwrap();
+        let keys: Vec<&str> = result.keys().map(|k| k.as_str()).collect();
+        assert_eq!(keys, vec!["SEC_DB-PASSWORD", "SEC_TOKEN"]);
+        assert_eq!(
+            result.get("SEC_DB-PASSWORD"),
+            Some(&SecValueType::sec_from("s3cr3t".to_string()))
+        );
+        assert_eq!(
+            result.get("SEC_TOKEN"),
+            Some(&SecValueType::sec_from("abc".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_load_sec_dir_by_plain_dir() {
+        with_temp_home(|home_path| {
+            let sec_dir = home_path.join(".swarm").join("secrets");
+            fs::create_dir_all(sec_dir.join("nested")).unwrap();
+            fs::write(sec_dir.join("api_key"), "k").unwrap();
+            fs::write(sec_dir.join(".hidden"), "h").unwrap();
+
+            let result = load_sec_dir_by(".swarm", "secrets").unwrap();
+            assert_eq!(result.len(), 1);
+            assert!(result.contains_key("SEC_API_KEY"));
+            assert!(load_sec_dir_by(".swarm", "missing").unwrap().is_empty());
+        });
+    }
+
     #[test]
     fn test_load_sec_dict_by_yaml() {
         with_temp_home(|home_path| {
@@ -334,7 +938,7 @@ mod tests {
 
     impl HomeGuard {
         fn set(path: &Path) -> Self {
-            let lock = home_lock().lock().unwrap_or_else(|err| err.into_inner());
+            let lock = crate::env::env_lock();
             let old_home = env::var_os("HOME");
             unsafe {
                 env::set_var("HOME", path);
@@ -361,11 +965,6 @@ mod tests {
         }
     }
 
-    fn home_lock() -> &'static Mutex<()> {
-        static HOME_MUTEX: OnceLock<Mutex<()>> = OnceLock::new();
-        HOME_MUTEX.get_or_init(|| Mutex::new(()))
-    }
-
     struct CurrentDirGuard {
         old_dir: PathBuf,
         _lock: MutexGuard<'static, ()>,
@@ -0,0 +1,431 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeDelta, Utc};
+use indexmap::IndexMap;
+use orion_error::{ToStructError, UvsFrom};
+use orion_variate::vars::ValueType;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    error::{OrionSecReason, SecError, SecReason, SecResult},
+    query::SecPath,
+    sec::{SecList, SecObj, SecValueObj, SecValueType},
+    visit::{SecVisitor, visit},
+};
+
+/// 密文文件中携带元数据的保留键：
+///
+/// ```yaml
+/// db_pass:
+///   value: s3cr3t
+///   _meta:
+///     created_at: 2026-01-01
+///     expires_at: 2026-12-01T00:00:00Z
+///     rotation: 30d
+/// ```
+pub const META_KEY: &str = "_meta";
+/// 与 [`META_KEY`] 配对的取值键。
+pub const META_VALUE_KEY: &str = "value";
+
+/// 单个密文的生命周期元数据。
+///
+/// 时间为 UTC；`rotation` 在文件中写作 `30d`、`12h`、`15m`、`45s`、`2w` 或秒数。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<DateTime<Utc>>,
+    #[serde(with = "rotation_serde", skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<Duration>,
+}
+
+impl SecMeta {
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|t| t <= now)
+    }
+
+    /// 已到 `not_before` 且尚未过期。
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.not_before.is_none_or(|t| t <= now) && !self.is_expired_at(now)
+    }
+
+    /// 在 `now + window` 之前过期（含已过期）；`now + window` 超出时间范围时视为无限远。
+    pub fn expires_within(&self, now: DateTime<Utc>, window: Duration) -> bool {
+        self.expires_at.is_some_and(|t| {
+            now.checked_add_signed(window)
+                .is_none_or(|limit| t <= limit)
+        })
+    }
+
+    /// 下次应轮换的时间：`created_at + rotation`，两者缺一或超出时间范围时为 `None`。
+    pub fn rotation_due(&self) -> Option<DateTime<Utc>> {
+        self.created_at?.checked_add_signed(self.rotation?)
+    }
+
+    /// 未生效或已过期时返回 `SecReason::Expired`。
+    pub fn check_at(&self, now: DateTime<Utc>) -> SecResult<()> {
+        if let Some(t) = self.not_before
+            && now < t
+        {
+            return expired_error(format!("not valid before {}", t.to_rfc3339()));
+        }
+        if let Some(t) = self.expires_at
+            && t <= now
+        {
+            return expired_error(format!("expired at {}", t.to_rfc3339()));
+        }
+        Ok(())
+    }
+
+    /// 从密文文件中 `_meta` 对象解析，未知字段与无法识别的时间返回错误。
+    pub fn from_value(value: &ValueType) -> SecResult<Self> {
+        let ValueType::Obj(obj) = value else {
+            return Err(meta_error(format!("`{META_KEY}` must be a mapping")));
+        };
+        let mut meta = SecMeta::default();
+        for (k, v) in obj {
+            match k.to_lowercase().as_str() {
+                "created_at" => meta.created_at = Some(parse_time(k, v)?),
+                "expires_at" => meta.expires_at = Some(parse_time(k, v)?),
+                "not_before" => meta.not_before = Some(parse_time(k, v)?),
+                "rotation" => {
+                    meta.rotation = Some(match v {
+                        ValueType::Number(secs) => i64::try_from(*secs)
+                            .ok()
+                            .and_then(TimeDelta::try_seconds)
+                            .ok_or_else(|| meta_error(format!("invalid `{k}`: {secs}")))?,
+                        ValueType::String(s) => parse_duration(s)?,
+                        _ => return Err(meta_error(format!("invalid `{k}`"))),
+                    })
+                }
+                _ => return Err(meta_error(format!("unknown meta field `{k}`"))),
+            }
+        }
+        Ok(meta)
+    }
+
+    /// [`SecMeta::from_value`] 的逆操作，写回密文文件时使用。
+    pub fn to_value(&self) -> ValueType {
+        let mut obj = IndexMap::new();
+        for (name, time) in [
+            ("created_at", self.created_at),
+            ("expires_at", self.expires_at),
+            ("not_before", self.not_before),
+        ] {
+            if let Some(t) = time {
+                obj.insert(name.to_string(), ValueType::String(t.to_rfc3339()));
+            }
+        }
+        if let Some(rotation) = self.rotation {
+            obj.insert(
+                "rotation".to_string(),
+                ValueType::String(format_duration(rotation)),
+            );
+        }
+        ValueType::Obj(obj)
+    }
+}
+
+/// 路径及其过期时间。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecExpiry {
+    pub path: SecPath,
+    pub at: DateTime<Utc>,
+}
+
+/// 在 `now + window` 之前过期的全部节点（含已过期），按过期时间排序。
+pub fn expiring_within(obj: &SecValueObj, now: DateTime<Utc>, window: Duration) -> Vec<SecExpiry> {
+    let mut found = collect(obj, |meta| {
+        meta.expires_within(now, window)
+            .then_some(meta.expires_at)
+            .flatten()
+    });
+    found.sort_by_key(|e| e.at);
+    found
+}
+
+/// 已过期的全部节点，按过期时间排序。
+pub fn expired(obj: &SecValueObj, now: DateTime<Utc>) -> Vec<SecExpiry> {
+    expiring_within(obj, now, Duration::zero())
+}
+
+/// 到期需要轮换的节点（`created_at + rotation <= now`），按应轮换时间排序。
+pub fn rotation_due(obj: &SecValueObj, now: DateTime<Utc>) -> Vec<SecExpiry> {
+    let mut found = collect(obj, |meta| meta.rotation_due().filter(|t| *t <= now));
+    found.sort_by_key(|e| e.at);
+    found
+}
+
+/// 存在未生效或已过期的节点时返回 `SecReason::Expired`，错误中只列出路径。
+pub fn check_expiry(obj: &SecValueObj, now: DateTime<Utc>) -> SecResult<()> {
+    let bad = collect(obj, |meta| {
+        meta.check_at(now)
+            .is_err()
+            .then(|| meta.expires_at.or(meta.not_before))
+            .flatten()
+    });
+    if bad.is_empty() {
+        return Ok(());
+    }
+    let paths = bad
+        .iter()
+        .map(|e| e.path.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    expired_error(format!("inactive secrets: {paths}"))
+}
+
+struct Collect<F> {
+    pick: F,
+    found: Vec<SecExpiry>,
+}
+
+impl<F> Collect<F>
+where
+    F: FnMut(&SecMeta) -> Option<DateTime<Utc>>,
+{
+    fn check(&mut self, path: &SecPath, meta: Option<&SecMeta>) {
+        if let Some(at) = meta.and_then(&mut self.pick) {
+            self.found.push(SecExpiry {
+                path: path.clone(),
+                at,
+            });
+        }
+    }
+}
+
+impl<F> SecVisitor for Collect<F>
+where
+    F: FnMut(&SecMeta) -> Option<DateTime<Utc>>,
+{
+    fn visit_leaf(&mut self, path: &SecPath, value: &SecValueType) {
+        self.check(path, value.meta());
+    }
+    fn enter_obj(&mut self, path: &SecPath, obj: &SecObj) -> bool {
+        self.check(path, obj.meta());
+        true
+    }
+    fn enter_list(&mut self, path: &SecPath, list: &SecList) -> bool {
+        self.check(path, list.meta());
+        true
+    }
+}
+
+fn collect<F>(obj: &SecValueObj, pick: F) -> Vec<SecExpiry>
+where
+    F: FnMut(&SecMeta) -> Option<DateTime<Utc>>,
+{
+    let mut collect = Collect {
+        pick,
+        found: Vec::new(),
+    };
+    visit(obj, &mut collect);
+    collect.found
+}
+
+fn parse_time(field: &str, value: &ValueType) -> SecResult<DateTime<Utc>> {
+    let ValueType::String(text) = value else {
+        return Err(meta_error(format!("invalid `{field}`")));
+    };
+    if let Ok(t) = DateTime::parse_from_rfc3339(text) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|t| t.and_utc())
+        .ok_or_else(|| meta_error(format!("invalid `{field}`: {text}")))
+}
+
+/// 解析 `30d`、`12h`、`15m`、`45s`、`2w` 或纯秒数。
+pub fn parse_duration(text: &str) -> SecResult<Duration> {
+    let text = text.trim();
+    let invalid = || meta_error(format!("invalid duration `{text}`"));
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (num, unit) = text.split_at(split);
+    let n: i64 = num.parse().map_err(|_| invalid())?;
+    let duration = match unit {
+        "" | "s" => TimeDelta::try_seconds(n),
+        "m" => TimeDelta::try_minutes(n),
+        "h" => TimeDelta::try_hours(n),
+        "d" => TimeDelta::try_days(n),
+        "w" => TimeDelta::try_weeks(n),
+        _ => None,
+    };
+    duration.ok_or_else(invalid)
+}
+
+/// [`parse_duration`] 的逆操作，取能整除的最大单位。
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds();
+    for (unit, size) in [("w", 604_800), ("d", 86_400), ("h", 3_600), ("m", 60)] {
+        if secs != 0 && secs % size == 0 {
+            return format!("{}{unit}", secs / size);
+        }
+    }
+    format!("{secs}s")
+}
+
+fn meta_error(detail: String) -> SecError {
+    OrionSecReason::from_validation()
+        .to_err()
+        .with_detail(detail)
+}
+
+fn expired_error<T>(detail: String) -> SecResult<T> {
+    OrionSecReason::Sec(SecReason::Expired(detail)).err_result()
+}
+
+mod rotation_serde {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(d) => s.serialize_str(&super::format_duration(*d)),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+        match Option::<String>::deserialize(d)? {
+            Some(text) => super::parse_duration(&text)
+                .map(Some)
+                .map_err(|_| D::Error::custom(format!("invalid duration `{text}`"))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::{SecFrom, SecString};
+    use crate::types::UniCaseMap;
+    use orion_error::ErrorCode;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn meta_with_expiry(text: &str) -> SecMeta {
+        SecMeta {
+            expires_at: Some(at(text)),
+            ..Default::default()
+        }
+    }
+
+    fn sample() -> SecValueObj {
+        let mut obj = UniCaseMap::new();
+        let mut soon = SecValueType::sec_from("a".to_string());
+        soon.set_meta(Some(meta_with_expiry("2026-10-20T00:00:00Z")));
+        let mut later = SecValueType::sec_from("b".to_string());
+        later.set_meta(Some(meta_with_expiry("2027-01-01T00:00:00Z")));
+        let mut gone = SecValueType::sec_from("c".to_string());
+        gone.set_meta(Some(meta_with_expiry("2026-10-01T00:00:00Z")));
+        obj.insert("SOON".into(), soon);
+        obj.insert("LATER".into(), later);
+        obj.insert("GONE".into(), gone);
+        obj.insert("PLAIN".into(), SecValueType::sec_from("d".to_string()));
+        obj
+    }
+
+    #[test]
+    fn test_meta_from_value() {
+        let mut raw = IndexMap::new();
+        raw.insert(
+            "created_at".to_string(),
+            ValueType::String("2026-01-01".into()),
+        );
+        raw.insert(
+            "expires_at".to_string(),
+            ValueType::String("2026-12-01T08:00:00+08:00".into()),
+        );
+        raw.insert("rotation".to_string(), ValueType::String("30d".into()));
+        let meta = SecMeta::from_value(&ValueType::Obj(raw.clone())).unwrap();
+        assert_eq!(meta.created_at, Some(at("2026-01-01T00:00:00Z")));
+        assert_eq!(meta.expires_at, Some(at("2026-12-01T00:00:00Z")));
+        assert_eq!(meta.rotation, Some(Duration::days(30)));
+        assert_eq!(meta.rotation_due(), Some(at("2026-01-31T00:00:00Z")));
+        assert_eq!(SecMeta::from_value(&meta.to_value()).unwrap(), meta);
+
+        raw.insert("owner".to_string(), ValueType::String("x".into()));
+        assert!(SecMeta::from_value(&ValueType::Obj(raw)).is_err());
+    }
+
+    #[test]
+    fn test_duration_roundtrip() {
+        for text in ["2w", "3d", "12h", "15m", "45s"] {
+            assert_eq!(format_duration(parse_duration(text).unwrap()), text);
+        }
+        assert_eq!(parse_duration("90").unwrap(), Duration::seconds(90));
+        assert!(parse_duration("1y").is_err());
+        assert!(parse_duration("99999999999999d").is_err());
+        assert!(parse_duration("9999999999999999999").is_err());
+
+        let mut raw = IndexMap::new();
+        raw.insert("rotation".to_string(), ValueType::Number(u64::MAX));
+        assert!(SecMeta::from_value(&ValueType::Obj(raw)).is_err());
+
+        let far = SecMeta {
+            created_at: Some(DateTime::<Utc>::MAX_UTC),
+            expires_at: Some(DateTime::<Utc>::MAX_UTC),
+            rotation: Some(Duration::days(1)),
+            ..SecMeta::default()
+        };
+        assert_eq!(far.rotation_due(), None);
+        assert!(far.expires_within(Utc::now(), TimeDelta::MAX));
+    }
+
+    #[test]
+    fn test_expiring_within_and_expired() {
+        let now = at("2026-10-18T00:00:00Z");
+        let obj = sample();
+        let soon: Vec<String> = expiring_within(&obj, now, Duration::days(7))
+            .into_iter()
+            .map(|e| e.path.to_string())
+            .collect();
+        assert_eq!(soon, vec!["GONE", "SOON"]);
+        let gone = expired(&obj, now);
+        assert_eq!(gone.len(), 1);
+        assert_eq!(gone[0].at, at("2026-10-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_check_expiry_and_checked_value() {
+        let now = at("2026-10-18T00:00:00Z");
+        let err = check_expiry(&sample(), now).unwrap_err();
+        assert_eq!(err.error_code(), 501);
+        assert!(format!("{err}").contains("GONE"));
+
+        let value = SecString::sec_from("tok".to_string()).with_meta(SecMeta {
+            not_before: Some(at("2026-11-01T00:00:00Z")),
+            ..Default::default()
+        });
+        assert!(value.checked_value_at(now).is_err());
+        assert_eq!(
+            value.checked_value_at(at("2026-11-02T00:00:00Z")).unwrap(),
+            "tok"
+        );
+    }
+
+    #[test]
+    fn test_rotation_due() {
+        let mut obj = UniCaseMap::new();
+        let mut v = SecValueType::sec_from("a".to_string());
+        v.set_meta(Some(SecMeta {
+            created_at: Some(at("2026-09-01T00:00:00Z")),
+            rotation: Some(Duration::days(30)),
+            ..Default::default()
+        }));
+        obj.insert("KEY".into(), v);
+        assert_eq!(rotation_due(&obj, at("2026-10-18T00:00:00Z")).len(), 1);
+        assert!(rotation_due(&obj, at("2026-09-15T00:00:00Z")).is_empty());
+    }
+}
@@ -0,0 +1,638 @@
+use std::ops::Range;
+
+use orion_error::{ErrorOwe, ToStructError, UvsFrom};
+use saphyr_parser::{Event, Parser, ScalarStyle};
+use serde_json::Value as JsonValue;
+use toml_edit::{DocumentMut, Item};
+
+use crate::{
+    error::{OrionSecReason, SecError, SecResult},
+    load::{SecFileFmt, file_value},
+    meta::{META_KEY, META_VALUE_KEY},
+    query::{PathSeg, SecPath},
+    sec::SecValueType,
+};
+
+/// 在密文文件原文上只改写 `updates` 指向的节点，其余键的拼写、顺序与取值保持原样。
+///
+/// 键按大小写不敏感匹配，`{ value, _meta }` 包装会被穿透；新增的键名写为小写。
+/// TOML 与 YAML 只改写目标节点所在的文本，注释、锚点与排版保持不变；
+/// 结构变化（如元数据增删键）的节点以 JSON 风格的行内写法整体替换。JSON 按原结构重新输出。
+/// 经由 YAML 别名或合并键（`<<`）才能到达的路径无法原地改写，返回错误。
+pub(crate) fn patch_secfile(
+    text: &str,
+    fmt: SecFileFmt,
+    updates: &[(&SecPath, &SecValueType)],
+) -> SecResult<String> {
+    let mut out = match fmt {
+        SecFileFmt::Yaml | SecFileFmt::Json => {
+            let mut doc: JsonValue = if fmt == SecFileFmt::Yaml {
+                serde_yaml::from_str(text).owe_data()?
+            } else {
+                serde_json::from_str(text).owe_data()?
+            };
+            let mut news = Vec::with_capacity(updates.len());
+            for (path, value) in updates {
+                let new = serde_json::to_value(file_value(value)).owe_data()?;
+                if !patch_json(&mut doc, path.segments(), new.clone()) {
+                    return Err(not_found(path));
+                }
+                news.push((*path, new));
+            }
+            if fmt == SecFileFmt::Yaml {
+                let out = patch_yaml(text, &news)?;
+                // 原地改写的结果必须与按结构修改后的文档一致
+                let check: JsonValue = serde_yaml::from_str(&out).owe_data()?;
+                if check != doc {
+                    return Err(yaml_error(
+                        "secret file cannot be rewritten in place".to_string(),
+                    ));
+                }
+                return Ok(out);
+            }
+            serde_json::to_string_pretty(&doc).owe_data()?
+        }
+        SecFileFmt::Toml => {
+            let mut doc: DocumentMut = text.parse().owe_data()?;
+            for (path, value) in updates {
+                let new = toml::Value::try_from(file_value(value)).owe_data()?;
+                if !patch_toml(doc.as_item_mut(), path.segments(), &new)? {
+                    return Err(not_found(path));
+                }
+            }
+            doc.to_string()
+        }
+        SecFileFmt::Dir => {
+            return OrionSecReason::from_validation()
+                .to_err()
+                .with_detail("secret directories are read-only")
+                .err();
+        }
+    };
+    if text.ends_with('\n') && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn same_key(file_key: &str, key: &str) -> bool {
+    file_key.to_uppercase() == key.to_uppercase()
+}
+
+fn patch_json(node: &mut JsonValue, path: &[PathSeg], new: JsonValue) -> bool {
+    let Some((seg, rest)) = path.split_first() else {
+        merge_json(node, new);
+        return true;
+    };
+    if let JsonValue::Object(map) = node
+        && map.keys().any(|k| same_key(k, META_KEY))
+    {
+        return match map.iter_mut().find(|(k, _)| same_key(k, META_VALUE_KEY)) {
+            Some((_, inner)) => patch_json(inner, path, new),
+            None => false,
+        };
+    }
+    let child = match (seg, node) {
+        (PathSeg::Key(key), JsonValue::Object(map)) => map
+            .iter_mut()
+            .find(|(k, _)| same_key(k, key.as_str()))
+            .map(|(_, v)| v),
+        (PathSeg::Index(i), JsonValue::Array(list)) => list.get_mut(*i),
+        _ => None,
+    };
+    child.is_some_and(|c| patch_json(c, rest, new))
+}
+
+fn merge_json(old: &mut JsonValue, new: JsonValue) {
+    let (JsonValue::Object(map), JsonValue::Object(next)) = (&mut *old, &new) else {
+        *old = new;
+        return;
+    };
+    map.retain(|k, _| next.keys().any(|n| same_key(k, n)));
+    let JsonValue::Object(next) = new else {
+        return;
+    };
+    for (key, value) in next {
+        match map.iter_mut().find(|(k, _)| same_key(k, &key)) {
+            Some((_, slot)) => merge_json(slot, value),
+            None => {
+                map.insert(key.to_lowercase(), value);
+            }
+        }
+    }
+}
+
+fn patch_toml(item: &mut Item, path: &[PathSeg], new: &toml::Value) -> SecResult<bool> {
+    let Some((seg, rest)) = path.split_first() else {
+        merge_toml(item, new)?;
+        return Ok(true);
+    };
+    if let Some(table) = item.as_table_like_mut()
+        && table.iter().any(|(k, _)| same_key(k, META_KEY))
+    {
+        return match find_toml_key(table, META_VALUE_KEY).and_then(|k| table.get_mut(&k)) {
+            Some(inner) => patch_toml(inner, path, new),
+            None => Ok(false),
+        };
+    }
+    match seg {
+        PathSeg::Key(key) => {
+            let Some(table) = item.as_table_like_mut() else {
+                return Ok(false);
+            };
+            match find_toml_key(table, key.as_str()).and_then(|k| table.get_mut(&k)) {
+                Some(child) => patch_toml(child, rest, new),
+                None => Ok(false),
+            }
+        }
+        PathSeg::Index(i) => match item {
+            Item::ArrayOfTables(list) => {
+                let Some(slot) = list.get_mut(*i) else {
+                    return Ok(false);
+                };
+                let mut child = Item::Table(std::mem::take(slot));
+                let found = patch_toml(&mut child, rest, new);
+                *slot = child
+                    .into_table()
+                    .map_err(|_| toml_error("array of tables element must stay a table"))?;
+                found
+            }
+            Item::Value(toml_edit::Value::Array(list)) => {
+                let Some(slot) = list.get_mut(*i) else {
+                    return Ok(false);
+                };
+                let mut child = Item::Value(slot.clone());
+                let found = patch_toml(&mut child, rest, new)?;
+                *slot = child
+                    .into_value()
+                    .map_err(|_| toml_error("array element must stay a value"))?;
+                Ok(found)
+            }
+            _ => Ok(false),
+        },
+    }
+}
+
+fn merge_toml(item: &mut Item, new: &toml::Value) -> SecResult<()> {
+    if let toml::Value::Table(next) = new
+        && let Some(table) = item.as_table_like_mut()
+    {
+        let stale: Vec<String> = table
+            .iter()
+            .map(|(k, _)| k.to_string())
+            .filter(|k| !next.keys().any(|n| same_key(k, n)))
+            .collect();
+        for key in stale {
+            table.remove(&key);
+        }
+        for (key, value) in next {
+            match find_toml_key(table, key).and_then(|k| table.get_mut(&k)) {
+                Some(child) => merge_toml(child, value)?,
+                None => {
+                    table.insert(&key.to_lowercase(), Item::Value(edit_value(value)?));
+                }
+            }
+        }
+        return Ok(());
+    }
+    let mut value = edit_value(new)?;
+    if let Item::Value(old) = item {
+        *value.decor_mut() = old.decor().clone();
+    }
+    *item = Item::Value(value);
+    Ok(())
+}
+
+fn find_toml_key(table: &dyn toml_edit::TableLike, key: &str) -> Option<String> {
+    table
+        .iter()
+        .map(|(k, _)| k)
+        .find(|k| same_key(k, key))
+        .map(str::to_string)
+}
+
+fn edit_value(value: &toml::Value) -> SecResult<toml_edit::Value> {
+    value
+        .to_string()
+        .parse()
+        .map_err(|_| toml_error("value cannot be written as toml"))
+}
+
+/// YAML 节点及其在原文中的字节范围。
+struct YamlNode {
+    span: Range<usize>,
+    kind: YamlKind,
+}
+
+enum YamlKind {
+    /// `empty` 表示 `key:` 这样省略的值，改写时需在冒号后补空格。
+    Scalar {
+        value: String,
+        plain: bool,
+        empty: bool,
+    },
+    Map(Vec<(String, YamlNode)>),
+    Seq(Vec<YamlNode>),
+    Alias,
+}
+
+impl YamlNode {
+    /// 原值按 YAML 规则解释后的 JSON 形式；容器与别名返回 `None`。
+    fn scalar_json(&self) -> Option<JsonValue> {
+        let YamlKind::Scalar { value, plain, .. } = &self.kind else {
+            return None;
+        };
+        if !*plain {
+            return Some(JsonValue::String(value.clone()));
+        }
+        Some(serde_yaml::from_str(value).unwrap_or_else(|_| JsonValue::String(value.clone())))
+    }
+
+    fn entry(&self, key: &str) -> Option<&YamlNode> {
+        let YamlKind::Map(entries) = &self.kind else {
+            return None;
+        };
+        entries
+            .iter()
+            .find(|(k, _)| same_key(k, key))
+            .map(|(_, v)| v)
+    }
+}
+
+/// 按事件流构建带原文位置的节点树（saphyr 的位置以字符计，这里换算为字节）。
+struct YamlReader<'a> {
+    text: &'a str,
+    events: std::vec::IntoIter<(Event<'a>, Range<usize>)>,
+    last_end: usize,
+}
+
+impl<'a> YamlReader<'a> {
+    fn parse(text: &'a str) -> SecResult<Option<YamlNode>> {
+        let offsets: Vec<usize> = text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([text.len()])
+            .collect();
+        let mut events = Vec::new();
+        for item in Parser::new_from_str(text) {
+            let (event, span) = item.map_err(|e| yaml_error(e.to_string()))?;
+            let range = offsets[span.start.index()]..offsets[span.end.index()];
+            events.push((event, range));
+        }
+        let mut reader = YamlReader {
+            text,
+            events: events.into_iter(),
+            last_end: 0,
+        };
+        loop {
+            match reader.events.next() {
+                Some((Event::DocumentStart(_), span)) => {
+                    reader.last_end = span.end;
+                    return reader.node().map(Some);
+                }
+                Some(_) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    fn node(&mut self) -> SecResult<YamlNode> {
+        let Some((event, span)) = self.events.next() else {
+            return Err(yaml_error("unexpected end of yaml".to_string()));
+        };
+        let prev_end = std::mem::replace(&mut self.last_end, span.end);
+        match event {
+            Event::Scalar(value, style, ..) => {
+                self.scalar(value.into_owned(), style, span, prev_end)
+            }
+            Event::Alias(_) => Ok(YamlNode {
+                span,
+                kind: YamlKind::Alias,
+            }),
+            Event::SequenceStart(..) => {
+                let mut items = Vec::new();
+                let end = loop {
+                    if let Some((Event::SequenceEnd, _)) = self.events.as_slice().first() {
+                        let (_, end) = self.events.next().expect("peeked");
+                        self.last_end = end.end;
+                        break end.end;
+                    }
+                    items.push(self.node()?);
+                };
+                let flow = self.text[span.start..].starts_with('[');
+                let end = match items.last() {
+                    Some(last) if !flow => last.span.end,
+                    _ => end,
+                };
+                Ok(YamlNode {
+                    span: span.start..end,
+                    kind: YamlKind::Seq(items),
+                })
+            }
+            Event::MappingStart(..) => {
+                let mut entries = Vec::new();
+                let end = loop {
+                    if let Some((Event::MappingEnd, _)) = self.events.as_slice().first() {
+                        let (_, end) = self.events.next().expect("peeked");
+                        self.last_end = end.end;
+                        break end.end;
+                    }
+                    let key = match self.node()?.kind {
+                        YamlKind::Scalar { value, .. } => value,
+                        _ => String::new(),
+                    };
+                    entries.push((key, self.node()?));
+                };
+                let flow = self.text[span.start..].starts_with('{');
+                let end = match entries.last() {
+                    Some((_, last)) if !flow => last.span.end,
+                    _ => end,
+                };
+                Ok(YamlNode {
+                    span: span.start..end,
+                    kind: YamlKind::Map(entries),
+                })
+            }
+            _ => Err(yaml_error("unexpected yaml event".to_string())),
+        }
+    }
+
+    fn scalar(
+        &self,
+        value: String,
+        style: ScalarStyle,
+        span: Range<usize>,
+        prev_end: usize,
+    ) -> SecResult<YamlNode> {
+        let text = self.text;
+        let (span, empty) = match style {
+            // 块标量的位置从内容开始，需向前包含 `|`/`>` 指示符，向后去掉尾随空白
+            ScalarStyle::Literal | ScalarStyle::Folded => {
+                let header = text[..span.start]
+                    .trim_end_matches([' ', '\t', '\n', '\r'])
+                    .rfind('\n')
+                    .map_or(0, |i| i + 1)
+                    .max(prev_end);
+                let indicator = text[header..span.start]
+                    .find(['|', '>'])
+                    .map(|i| header + i)
+                    .ok_or_else(|| yaml_error("block scalar without indicator".to_string()))?;
+                let end = indicator + text[indicator..span.end].trim_end().len();
+                (indicator..end, false)
+            }
+            _ if span.is_empty() => {
+                let line_end = text[span.start..]
+                    .find('\n')
+                    .map_or(text.len(), |i| span.start + i);
+                let colon = text[span.start..line_end]
+                    .find(':')
+                    .map(|i| span.start + i + 1)
+                    .ok_or_else(|| yaml_error("empty value without key".to_string()))?;
+                (colon..colon, true)
+            }
+            _ => (span, false),
+        };
+        Ok(YamlNode {
+            span,
+            kind: YamlKind::Scalar {
+                value,
+                plain: style == ScalarStyle::Plain,
+                empty,
+            },
+        })
+    }
+}
+
+fn patch_yaml(text: &str, updates: &[(&SecPath, JsonValue)]) -> SecResult<String> {
+    let Some(root) = YamlReader::parse(text)? else {
+        return Err(yaml_error("empty yaml document".to_string()));
+    };
+    let mut edits = Vec::new();
+    for (path, new) in updates {
+        let node = find_yaml(&root, path.segments()).map_err(|detail| {
+            OrionSecReason::from_validation()
+                .to_err()
+                .with_detail(format!("`{path}` {detail}"))
+        })?;
+        merge_yaml(node, new, &mut edits);
+    }
+    edits.sort_by_key(|(span, _)| span.start);
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    for (span, new) in edits {
+        if span.start < pos {
+            return Err(yaml_error("overlapping yaml updates".to_string()));
+        }
+        out.push_str(&text[pos..span.start]);
+        out.push_str(&new);
+        pos = span.end;
+    }
+    out.push_str(&text[pos..]);
+    Ok(out)
+}
+
+fn find_yaml<'n>(node: &'n YamlNode, path: &[PathSeg]) -> Result<&'n YamlNode, &'static str> {
+    if matches!(node.kind, YamlKind::Alias) {
+        return Err("goes through a yaml alias and cannot be rewritten in place");
+    }
+    let Some((seg, rest)) = path.split_first() else {
+        return Ok(node);
+    };
+    if node.entry(META_KEY).is_some() {
+        return match node.entry(META_VALUE_KEY) {
+            Some(inner) => find_yaml(inner, path),
+            None => Err("not found in secret file"),
+        };
+    }
+    let child = match (seg, &node.kind) {
+        (PathSeg::Key(key), YamlKind::Map(_)) => match node.entry(key.as_str()) {
+            Some(child) => child,
+            None if node.entry("<<").is_some() => {
+                return Err("comes from a yaml merge key and cannot be rewritten in place");
+            }
+            None => return Err("not found in secret file"),
+        },
+        (PathSeg::Index(i), YamlKind::Seq(items)) => {
+            items.get(*i).ok_or("not found in secret file")?
+        }
+        _ => return Err("not found in secret file"),
+    };
+    find_yaml(child, rest)
+}
+
+/// 与 [`merge_json`] 语义一致：键集合相同的映射逐键递归，标量只在取值变化时改写，
+/// 其余情况把整个节点替换为行内写法。
+fn merge_yaml(node: &YamlNode, new: &JsonValue, edits: &mut Vec<(Range<usize>, String)>) {
+    let text = match (&node.kind, new) {
+        (YamlKind::Map(entries), JsonValue::Object(next))
+            if entries.len() == next.len()
+                && entries
+                    .iter()
+                    .all(|(k, _)| next.keys().any(|n| same_key(k, n))) =>
+        {
+            for (key, child) in entries {
+                if let Some((_, value)) = next.iter().find(|(n, _)| same_key(key, n)) {
+                    merge_yaml(child, value, edits);
+                }
+            }
+            return;
+        }
+        (YamlKind::Scalar { .. }, JsonValue::Object(_) | JsonValue::Array(_)) => flow_json(new),
+        (YamlKind::Scalar { .. }, _) if node.scalar_json().as_ref() == Some(new) => return,
+        _ => merged_flow(node, new),
+    };
+    let prefix = match node.kind {
+        YamlKind::Scalar { empty: true, .. } => " ",
+        _ => "",
+    };
+    edits.push((node.span.clone(), format!("{prefix}{text}")));
+}
+
+/// 保留原有键的拼写、新增键写为小写后的行内写法。
+fn merged_flow(node: &YamlNode, new: &JsonValue) -> String {
+    let (YamlKind::Map(entries), JsonValue::Object(next)) = (&node.kind, new) else {
+        return flow_json(new);
+    };
+    let mut items = Vec::new();
+    for (key, child) in entries {
+        if let Some((_, value)) = next.iter().find(|(n, _)| same_key(key, n)) {
+            items.push(format!(
+                "{}: {}",
+                flow_json(&JsonValue::from(key.as_str())),
+                merged_flow(child, value)
+            ));
+        }
+    }
+    for (key, value) in next {
+        if !entries.iter().any(|(k, _)| same_key(k, key)) {
+            items.push(format!(
+                "{}: {}",
+                flow_json(&JsonValue::from(key.to_lowercase())),
+                flow_json(value)
+            ));
+        }
+    }
+    format!("{{{}}}", items.join(", "))
+}
+
+/// JSON 是 YAML 行内写法的子集，在块与行内上下文中都可直接使用。
+fn flow_json(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Object(map) => {
+            let items: Vec<String> = map
+                .iter()
+                .map(|(k, v)| {
+                    format!(
+                        "{}: {}",
+                        flow_json(&JsonValue::from(k.as_str())),
+                        flow_json(v)
+                    )
+                })
+                .collect();
+            format!("{{{}}}", items.join(", "))
+        }
+        JsonValue::Array(list) => {
+            let items: Vec<String> = list.iter().map(flow_json).collect();
+            format!("[{}]", items.join(", "))
+        }
+        other => other.to_string(),
+    }
+}
+
+fn yaml_error(detail: String) -> SecError {
+    OrionSecReason::from_validation()
+        .to_err()
+        .with_detail(detail)
+}
+
+fn not_found(path: &SecPath) -> SecError {
+    OrionSecReason::from_validation()
+        .to_err()
+        .with_detail(format!("`{path}` not found in secret file"))
+}
+
+fn toml_error(detail: &str) -> SecError {
+    OrionSecReason::from_validation()
+        .to_err()
+        .with_detail(detail.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        meta::{SecMeta, parse_duration},
+        sec::SecFrom,
+    };
+    use chrono::{TimeZone, Utc};
+
+    fn sec(v: &str) -> SecValueType {
+        SecValueType::sec_from(v.to_string())
+    }
+
+    fn patch(text: &str, fmt: SecFileFmt, path: &str, value: &SecValueType) -> SecResult<String> {
+        let path: SecPath = path.parse().unwrap();
+        patch_secfile(text, fmt, &[(&path, value)])
+    }
+
+    #[test]
+    fn test_patch_yaml_keeps_comments_and_anchors() {
+        let text = "# managed by ops\nbase: &base\n  user: app\nsvc:\n  <<: *base\n  Pass: old # rotate monthly\n  port: 7\n";
+        let out = patch(text, SecFileFmt::Yaml, "svc.pass", &sec("n\"ew")).unwrap();
+        assert_eq!(
+            out,
+            "# managed by ops\nbase: &base\n  user: app\nsvc:\n  <<: *base\n  Pass: \"n\\\"ew\" # rotate monthly\n  port: 7\n"
+        );
+        let out = patch(text, SecFileFmt::Yaml, "base.user", &sec("app")).unwrap();
+        assert_eq!(out, text);
+    }
+
+    #[test]
+    fn test_patch_yaml_block_empty_and_flow_values() {
+        let text = "key: |\n  line1\n  line2\nnone:\nflow: {a: 1, b: [x, y]} # inline\nlast: 1\n";
+        let out = patch(text, SecFileFmt::Yaml, "key", &sec("a\nb")).unwrap();
+        assert_eq!(out, text.replace("|\n  line1\n  line2", "\"a\\nb\""));
+        let out = patch(text, SecFileFmt::Yaml, "none", &sec("x")).unwrap();
+        assert_eq!(out, text.replace("none:\n", "none: \"x\"\n"));
+        let out = patch(text, SecFileFmt::Yaml, "flow.b[1]", &sec("z")).unwrap();
+        assert_eq!(out, text.replace("[x, y]", "[x, \"z\"]"));
+    }
+
+    #[test]
+    fn test_patch_yaml_meta_wrapper_structural_change() {
+        let text = "tok:\n  value: t # current\n  _meta:\n    rotation: 30d # monthly\nafter: 1\n";
+        let mut value = sec("new");
+        value.set_meta(Some(SecMeta {
+            created_at: Some(Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap()),
+            rotation: Some(parse_duration("30d").unwrap()),
+            ..Default::default()
+        }));
+        let out = patch(text, SecFileFmt::Yaml, "tok", &value).unwrap();
+        assert_eq!(
+            out,
+            "tok:\n  value: \"new\" # current\n  _meta:\n    {\"rotation\": \"30d\", \"created_at\": \"2026-10-01T00:00:00+00:00\"} # monthly\nafter: 1\n"
+        );
+    }
+
+    #[test]
+    fn test_patch_yaml_refuses_alias_and_merge_paths() {
+        let text = "base: &base\n  user: app\nmerged:\n  <<: *base\nalias: *base\n";
+        assert!(patch(text, SecFileFmt::Yaml, "merged.user", &sec("x")).is_err());
+        let err = patch(text, SecFileFmt::Yaml, "alias.user", &sec("x")).unwrap_err();
+        assert!(format!("{err}").contains("alias"), "{err}");
+        let err = patch(text, SecFileFmt::Yaml, "missing", &sec("x")).unwrap_err();
+        assert!(format!("{err}").contains("not found"), "{err}");
+    }
+
+    #[test]
+    fn test_patch_toml_and_json() {
+        let text = "# top\n[DB]\npass = \"old\" # keep\nport = 1\n";
+        let out = patch(text, SecFileFmt::Toml, "db.PASS", &sec("new")).unwrap();
+        assert_eq!(out, "# top\n[DB]\npass = \"new\" # keep\nport = 1\n");
+
+        let text = "{\n  \"Db\": {\n    \"pass\": \"old\"\n  }\n}\n";
+        let out = patch(text, SecFileFmt::Json, "db.pass", &sec("new")).unwrap();
+        assert_eq!(out, "{\n  \"Db\": {\n    \"pass\": \"new\"\n  }\n}\n");
+        assert!(patch(text, SecFileFmt::Json, "db.user", &sec("x")).is_err());
+    }
+}
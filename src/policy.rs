@@ -0,0 +1,292 @@
+use std::path::Path;
+
+use log::info;
+use orion_conf::YamlIO;
+use orion_error::{ErrorOwe, ErrorWith, ToStructError};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    error::{OrionSecReason, SecReason, SecResult},
+    load::{GALAXY_DOT_DIR, dot_path},
+    query::{SecPath, SecQuery},
+    sec::SecValueObj,
+};
+
+const SEC_POLICY_FILE_NAME: &str = "sec_policy.yml";
+
+/// 声明哪些路径必须为密文、必须为明文或禁止出现的策略文件。
+///
+/// 每一项都是 [`SecQuery`] 表达式，例如：
+///
+/// ```yaml
+/// secret:
+///   - "**.password"
+///   - SEC_DB.url
+/// public:
+///   - SEC_APP.name
+/// forbidden:
+///   - "**.root_password"
+/// ```
+///
+/// 注意 `load_secfile_by` 会为顶层键加上 `SEC_` 前缀，策略中的路径需与之保持一致。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecPolicy {
+    pub secret: Vec<String>,
+    pub public: Vec<String>,
+    pub forbidden: Vec<String>,
+}
+
+struct CompiledPolicy {
+    secret: Vec<SecQuery>,
+    public: Vec<SecQuery>,
+    forbidden: Vec<SecQuery>,
+}
+
+impl SecPolicy {
+    pub fn load(path: &Path) -> SecResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let policy = Self::load_yaml(path).owe_logic().with(path)?;
+        policy.validate().with(path)?;
+        info!(target: "exec","  load policy {}", path.display());
+        Ok(policy)
+    }
+
+    /// 检查全部表达式均可解析。
+    pub fn validate(&self) -> SecResult<()> {
+        self.compile().map(|_| ())
+    }
+
+    fn compile(&self) -> SecResult<CompiledPolicy> {
+        let parse = |items: &[String]| {
+            items
+                .iter()
+                .map(|q| SecQuery::parse(q))
+                .collect::<SecResult<Vec<_>>>()
+        };
+        Ok(CompiledPolicy {
+            secret: parse(&self.secret)?,
+            public: parse(&self.public)?,
+            forbidden: parse(&self.forbidden)?,
+        })
+    }
+
+    /// 在加载后的 `SecValueObj` 上执行策略。
+    ///
+    /// - 命中 `forbidden` 的路径返回 `SecReason::NoPermission`；
+    /// - `secret` 与 `public` 选中的路径相同或互为前缀（明文嵌在密文子树内，或反之）
+    ///   返回 `SecReason::SensitiveMsg`；
+    /// - 其余按规则切换密级，`secret` 会覆盖整个子树。
+    ///
+    /// 出错时 `obj` 保持不变，错误信息只包含路径，不包含取值。
+    pub fn apply(&self, obj: &mut SecValueObj) -> SecResult<()> {
+        let compiled = self.compile()?;
+        self.check(&compiled, obj)?;
+        for query in &compiled.public {
+            query.mark_public(obj);
+        }
+        for query in &compiled.secret {
+            query.mark_secret(obj);
+        }
+        Ok(())
+    }
+
+    /// 只检查违规，不修改 `obj`。
+    pub fn check_obj(&self, obj: &SecValueObj) -> SecResult<()> {
+        let compiled = self.compile()?;
+        self.check(&compiled, obj)
+    }
+
+    fn check(&self, compiled: &CompiledPolicy, obj: &SecValueObj) -> SecResult<()> {
+        let forbidden = collect_paths(&compiled.forbidden, obj);
+        if !forbidden.is_empty() {
+            return OrionSecReason::Sec(SecReason::NoPermission(format!(
+                "forbidden path present: {}",
+                join_paths(&forbidden)
+            )))
+            .err_result();
+        }
+        let secret = collect_paths(&compiled.secret, obj);
+        let mut conflict = Vec::new();
+        for public in collect_paths(&compiled.public, obj) {
+            for secret in secret.iter().filter(|s| overlaps(s, &public)) {
+                if secret == &public {
+                    conflict.push(public.to_string());
+                } else {
+                    conflict.push(format!("{secret} (secret) / {public} (public)"));
+                }
+            }
+        }
+        if !conflict.is_empty() {
+            return OrionSecReason::Sec(SecReason::SensitiveMsg(format!(
+                "path declared both secret and public: {}",
+                conflict.join(", ")
+            )))
+            .err_result();
+        }
+        Ok(())
+    }
+}
+
+fn collect_paths(queries: &[SecQuery], obj: &SecValueObj) -> Vec<SecPath> {
+    let mut paths = Vec::new();
+    for query in queries {
+        for path in query.select_paths(obj) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+/// 两条路径相同或其中一条是另一条的前缀。
+fn overlaps(a: &SecPath, b: &SecPath) -> bool {
+    a.segments().starts_with(b.segments()) || b.segments().starts_with(a.segments())
+}
+
+fn join_paths(paths: &[SecPath]) -> String {
+    paths
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 读取 `~/.galaxy/sec_policy.yml`（优先当前目录下的 `.galaxy`），文件不存在时返回空策略。
+pub fn load_sec_policy() -> SecResult<SecPolicy> {
+    load_sec_policy_by(GALAXY_DOT_DIR, SEC_POLICY_FILE_NAME)
+}
+
+pub fn load_sec_policy_by(dot_name: &str, file_name: &str) -> SecResult<SecPolicy> {
+    SecPolicy::load(&dot_path(dot_name).join(file_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::{SecFrom, SecValueType, ValueGetter};
+    use crate::types::UniCaseMap;
+    use orion_error::ErrorCode;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn is_secret(obj: &SecValueObj, path: &str) -> bool {
+        matches!(obj.value_get(path), Some(SecValueType::String(v)) if v.is_secret())
+    }
+
+    fn sample() -> SecValueObj {
+        let mut db = UniCaseMap::new();
+        db.insert("url".into(), SecValueType::nor_from("pg://".to_string()));
+        db.insert("password".into(), SecValueType::nor_from("p".to_string()));
+        let mut root = UniCaseMap::new();
+        root.insert("SEC_DB".into(), SecValueType::from(db));
+        root.insert("SEC_NAME".into(), SecValueType::sec_from("app".to_string()));
+        root
+    }
+
+    #[test]
+    fn test_policy_apply_marks_paths() {
+        let policy = SecPolicy {
+            secret: vec!["**.password".into(), "sec_db.url".into()],
+            public: vec!["sec_name".into()],
+            forbidden: vec![],
+        };
+        let mut obj = sample();
+        policy.apply(&mut obj).unwrap();
+        assert!(is_secret(&obj, "SEC_DB.PASSWORD"));
+        assert!(is_secret(&obj, "SEC_DB.URL"));
+        assert!(!is_secret(&obj, "SEC_NAME"));
+    }
+
+    #[test]
+    fn test_policy_forbidden_path() {
+        let policy = SecPolicy {
+            forbidden: vec!["**.password".into()],
+            ..Default::default()
+        };
+        let mut obj = sample();
+        let err = policy.apply(&mut obj).unwrap_err();
+        assert_eq!(err.error_code(), 201);
+        let msg = format!("{err}");
+        assert!(msg.contains("SEC_DB.PASSWORD"));
+        assert!(!msg.contains("pg://"));
+        assert!(policy.check_obj(&UniCaseMap::new()).is_ok());
+    }
+
+    #[test]
+    fn test_policy_conflict_is_sensitive() {
+        let policy = SecPolicy {
+            secret: vec!["sec_db.*".into()],
+            public: vec!["**.url".into()],
+            forbidden: vec![],
+        };
+        let mut obj = sample();
+        let err = policy.apply(&mut obj).unwrap_err();
+        assert_eq!(err.error_code(), 101);
+        assert!(!is_secret(&obj, "SEC_DB.PASSWORD"));
+    }
+
+    #[test]
+    fn test_policy_conflict_on_nested_paths() {
+        for (secret, public, expect) in [
+            (
+                "sec_db",
+                "sec_db.url",
+                "SEC_DB (secret) / SEC_DB.URL (public)",
+            ),
+            (
+                "**.password",
+                "sec_db",
+                "SEC_DB.PASSWORD (secret) / SEC_DB (public)",
+            ),
+        ] {
+            let policy = SecPolicy {
+                secret: vec![secret.into()],
+                public: vec![public.into()],
+                forbidden: vec![],
+            };
+            let mut obj = sample();
+            let err = policy.apply(&mut obj).unwrap_err();
+            assert_eq!(err.error_code(), 101);
+            assert!(format!("{err}").contains(expect), "{err}");
+            assert!(!is_secret(&obj, "SEC_DB.URL"));
+        }
+        let policy = SecPolicy {
+            secret: vec!["sec_db".into()],
+            public: vec!["sec_name".into()],
+            forbidden: vec![],
+        };
+        assert!(policy.check_obj(&sample()).is_ok());
+    }
+
+    #[test]
+    fn test_policy_load_yaml() {
+        let mut file = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(file, "secret:").unwrap();
+        writeln!(file, "  - \"**.password\"").unwrap();
+        writeln!(file, "forbidden:").unwrap();
+        writeln!(file, "  - root_password").unwrap();
+
+        let policy = SecPolicy::load(file.path()).unwrap();
+        assert_eq!(policy.secret, vec!["**.password".to_string()]);
+        assert!(policy.public.is_empty());
+        assert_eq!(policy.forbidden, vec!["root_password".to_string()]);
+    }
+
+    #[test]
+    fn test_policy_load_invalid_query() {
+        let mut file = NamedTempFile::with_suffix(".yml").unwrap();
+        writeln!(file, "secret:").unwrap();
+        writeln!(file, "  - \"a[\"").unwrap();
+        assert!(SecPolicy::load(file.path()).is_err());
+    }
+
+    #[test]
+    fn test_policy_load_missing_file() {
+        let policy = SecPolicy::load(Path::new("/nonexistent/sec_policy.yml")).unwrap();
+        assert_eq!(policy, SecPolicy::default());
+    }
+}
@@ -0,0 +1,795 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use indexmap::IndexSet;
+use orion_error::{ToStructError, UvsFrom};
+
+use crate::{
+    error::{OrionSecReason, SecError, SecResult},
+    sec::{SecValueObj, SecValueType, ValueGetter},
+    types::UniString,
+};
+
+/// 路径中的一段：对象键或数组下标。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSeg {
+    Key(UniString),
+    Index(usize),
+}
+
+/// 指向 `SecValueObj` 中某个节点的具体路径，显示为 `A.B[0].C`。
+///
+/// 含有 `.`、`[`、`]`、`*` 等特殊字符的键会以双引号输出，
+/// 因此显示结果可以被 [`SecQuery::parse`] 原样解析回同一路径。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SecPath(Vec<PathSeg>);
+
+impl SecPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn segments(&self) -> &[PathSeg] {
+        &self.0
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn push(&mut self, seg: PathSeg) {
+        self.0.push(seg);
+    }
+    pub fn pop(&mut self) -> Option<PathSeg> {
+        self.0.pop()
+    }
+    #[must_use]
+    pub fn child(&self, seg: PathSeg) -> Self {
+        let mut path = self.clone();
+        path.push(seg);
+        path
+    }
+    /// 路径最后一个对象键，数组元素则取离它最近的键。
+    pub fn last_key(&self) -> Option<&UniString> {
+        self.0.iter().rev().find_map(|seg| match seg {
+            PathSeg::Key(k) => Some(k),
+            PathSeg::Index(_) => None,
+        })
+    }
+
+    pub fn get<'a>(&self, obj: &'a SecValueObj) -> Option<&'a SecValueType> {
+        let (first, rest) = self.0.split_first()?;
+        let mut current = match first {
+            PathSeg::Key(k) => obj.get(k)?,
+            PathSeg::Index(_) => return None,
+        };
+        for seg in rest {
+            current = match (seg, current) {
+                (PathSeg::Key(k), SecValueType::Obj(map)) => map.value().get(k)?,
+                (PathSeg::Index(i), SecValueType::List(list)) => list.value().get(*i)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    pub fn get_mut<'a>(&self, obj: &'a mut SecValueObj) -> Option<&'a mut SecValueType> {
+        let (first, rest) = self.0.split_first()?;
+        let mut current = match first {
+            PathSeg::Key(k) => obj.get_mut(k)?,
+            PathSeg::Index(_) => return None,
+        };
+        for seg in rest {
+            current = match (seg, current) {
+                (PathSeg::Key(k), SecValueType::Obj(map)) => map.value_mut().get_mut(k)?,
+                (PathSeg::Index(i), SecValueType::List(list)) => list.value_mut().get_mut(*i)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+}
+
+impl FromStr for SecPath {
+    type Err = SecError;
+
+    /// 解析 `A.B[0].C` 形式的具体路径，不允许通配符、过滤器与负数下标。
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            OrionSecReason::from_validation()
+                .to_err()
+                .with_detail(format!("`{s}` is not a concrete path"))
+        };
+        Parser::new(s)
+            .parse()?
+            .into_iter()
+            .map(|step| match step {
+                Step::Key(k) => Ok(PathSeg::Key(k)),
+                Step::Index(i) if i >= 0 => Ok(PathSeg::Index(i as usize)),
+                _ => Err(invalid()),
+            })
+            .collect::<SecResult<Vec<_>>>()
+            .map(SecPath)
+    }
+}
+
+impl From<Vec<PathSeg>> for SecPath {
+    fn from(value: Vec<PathSeg>) -> Self {
+        Self(value)
+    }
+}
+
+impl Display for PathSeg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathSeg::Key(k) if needs_quote(k.as_str()) => {
+                write!(
+                    f,
+                    "\"{}\"",
+                    k.as_str().replace('\\', "\\\\").replace('"', "\\\"")
+                )
+            }
+            PathSeg::Key(k) => write!(f, "{}", k.as_str()),
+            PathSeg::Index(i) => write!(f, "[{i}]"),
+        }
+    }
+}
+
+impl Display for SecPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, seg) in self.0.iter().enumerate() {
+            if i > 0 && matches!(seg, PathSeg::Key(_)) {
+                write!(f, ".")?;
+            }
+            write!(f, "{seg}")?;
+        }
+        Ok(())
+    }
+}
+
+fn needs_quote(key: &str) -> bool {
+    key.is_empty()
+        || key.chars().any(|c| {
+            matches!(c, '.' | '[' | ']' | '"' | '\'' | '*' | '?' | '\\') || c.is_whitespace()
+        })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(UniString),
+    Glob(String),
+    Wildcard,
+    Descend,
+    Index(i64),
+    Filter(Predicate),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Predicate {
+    path: String,
+    cond: Option<(bool, String)>,
+}
+
+impl Predicate {
+    fn test(&self, value: &SecValueType) -> bool {
+        let SecValueType::Obj(obj) = value else {
+            return false;
+        };
+        let Some(found) = obj.value().value_get(&self.path) else {
+            return false;
+        };
+        match &self.cond {
+            None => true,
+            Some((equal, expect)) => {
+                let same = leaf_text(&found).is_some_and(|text| text == *expect);
+                same == *equal
+            }
+        }
+    }
+}
+
+fn leaf_text(value: &SecValueType) -> Option<String> {
+    match value {
+        SecValueType::String(v) => Some(v.value().clone()),
+        SecValueType::Bool(v) => Some(v.value().to_string()),
+        SecValueType::Number(v) => Some(v.value().to_string()),
+        SecValueType::Float(v) => Some(v.value().to_string()),
+        SecValueType::Ip(v) => Some(v.value().to_string()),
+        SecValueType::Obj(_) | SecValueType::List(_) => None,
+    }
+}
+
+/// 作用于 `SecValueObj` 的路径查询。
+///
+/// 语法：
+/// - `A.B.C`：逐级按键访问（大小写不敏感）；
+/// - `*` / `[*]`：匹配对象或数组的全部子节点；
+/// - `**`：递归下降，匹配当前节点及其任意深度的后代；
+/// - `*_TOKEN`：键名通配，支持 `*` 与 `?`；
+/// - `"a.b"` / `['a.b']`：引号包裹的键按字面量匹配；
+/// - `[0]` / `[-1]`：数组下标，负数从末尾计数；
+/// - `[?name]`、`[?name=prod]`、`[?name!=prod]`：按子字段过滤子节点。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecQuery {
+    expr: String,
+    steps: Vec<Step>,
+}
+
+/// 一次查询命中的节点及其具体路径。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecMatch<'a> {
+    pub path: SecPath,
+    pub value: &'a SecValueType,
+}
+
+#[derive(Clone, Copy)]
+enum Cursor<'a> {
+    Root(&'a SecValueObj),
+    Node(&'a SecValueType),
+}
+
+impl<'a> Cursor<'a> {
+    fn obj(self) -> Option<&'a SecValueObj> {
+        match self {
+            Cursor::Root(obj) => Some(obj),
+            Cursor::Node(SecValueType::Obj(obj)) => Some(obj.value()),
+            Cursor::Node(_) => None,
+        }
+    }
+
+    fn children(self) -> Vec<(PathSeg, &'a SecValueType)> {
+        match self {
+            Cursor::Node(SecValueType::List(list)) => list
+                .value()
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (PathSeg::Index(i), v))
+                .collect(),
+            _ => self
+                .obj()
+                .map(|obj| {
+                    obj.iter()
+                        .map(|(k, v)| (PathSeg::Key(k.clone()), v))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl SecQuery {
+    pub fn parse(expr: &str) -> SecResult<Self> {
+        let steps = Parser::new(expr).parse()?;
+        Ok(Self {
+            expr: expr.to_string(),
+            steps,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expr
+    }
+
+    /// 返回所有命中节点，按遍历顺序排列且路径不重复。
+    pub fn select<'a>(&self, obj: &'a SecValueObj) -> Vec<SecMatch<'a>> {
+        let mut current: Vec<(SecPath, Cursor<'a>)> = vec![(SecPath::new(), Cursor::Root(obj))];
+        for step in &self.steps {
+            let mut next = Vec::new();
+            for (path, cursor) in current {
+                apply_step(step, &path, cursor, &mut next);
+            }
+            current = next;
+        }
+        let mut seen = IndexSet::new();
+        current
+            .into_iter()
+            .filter_map(|(path, cursor)| match cursor {
+                Cursor::Node(value) if !path.is_empty() => Some(SecMatch { path, value }),
+                _ => None,
+            })
+            .filter(|m| seen.insert(m.path.clone()))
+            .collect()
+    }
+
+    pub fn select_paths(&self, obj: &SecValueObj) -> Vec<SecPath> {
+        self.select(obj).into_iter().map(|m| m.path).collect()
+    }
+
+    /// 对每个命中节点执行 `f`，返回命中数量。
+    pub fn update<F>(&self, obj: &mut SecValueObj, mut f: F) -> usize
+    where
+        F: FnMut(&SecPath, &mut SecValueType),
+    {
+        let paths = self.select_paths(obj);
+        let mut count = 0;
+        for path in paths {
+            if let Some(value) = path.get_mut(obj) {
+                f(&path, value);
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// 将命中的节点（及其子树）标记为密文。
+    pub fn mark_secret(&self, obj: &mut SecValueObj) -> usize {
+        self.update(obj, |_, value| replace_with(value, SecValueType::to_sec))
+    }
+
+    /// 将命中的节点（及其子树）标记为明文。
+    ///
+    /// 若命中节点位于整体加密的容器中，该容器会被拆分为逐个子节点加密，
+    /// 其余兄弟节点仍保持密文。
+    pub fn mark_public(&self, obj: &mut SecValueObj) -> usize {
+        let paths = self.select_paths(obj);
+        for path in &paths {
+            split_secret_ancestors(obj, path);
+        }
+        self.update(obj, |_, value| replace_with(value, SecValueType::to_nor))
+    }
+}
+
+fn split_secret_ancestors(obj: &mut SecValueObj, path: &SecPath) {
+    let segs = path.segments();
+    for depth in 1..segs.len() {
+        let ancestor = SecPath::from(segs[..depth].to_vec());
+        match ancestor.get_mut(obj) {
+            Some(SecValueType::Obj(map)) if map.is_secret() => {
+                map.set_secret(false);
+                map.value_mut()
+                    .values_mut()
+                    .for_each(|v| replace_with(v, SecValueType::to_sec));
+            }
+            Some(SecValueType::List(list)) if list.is_secret() => {
+                list.set_secret(false);
+                list.value_mut()
+                    .iter_mut()
+                    .for_each(|v| replace_with(v, SecValueType::to_sec));
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn replace_with(value: &mut SecValueType, f: fn(SecValueType) -> SecValueType) {
+    let taken = std::mem::replace(value, SecValueType::from(Vec::new()));
+    *value = f(taken);
+}
+
+impl FromStr for SecQuery {
+    type Err = SecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for SecQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+fn apply_step<'a>(
+    step: &Step,
+    path: &SecPath,
+    cursor: Cursor<'a>,
+    out: &mut Vec<(SecPath, Cursor<'a>)>,
+) {
+    match step {
+        Step::Key(key) => {
+            if let Some(v) = cursor.obj().and_then(|obj| obj.get(key)) {
+                out.push((path.child(PathSeg::Key(key.clone())), Cursor::Node(v)));
+            }
+        }
+        Step::Glob(pattern) => {
+            if let Some(obj) = cursor.obj() {
+                for (k, v) in obj.iter() {
+                    if wildcard_match(pattern, k.as_str()) {
+                        out.push((path.child(PathSeg::Key(k.clone())), Cursor::Node(v)));
+                    }
+                }
+            }
+        }
+        Step::Wildcard => {
+            for (seg, v) in cursor.children() {
+                out.push((path.child(seg), Cursor::Node(v)));
+            }
+        }
+        Step::Descend => descend(path, cursor, out),
+        Step::Index(index) => {
+            if let Cursor::Node(SecValueType::List(list)) = cursor {
+                let list = list.value();
+                let resolved = if *index < 0 {
+                    list.len().checked_sub(index.unsigned_abs() as usize)
+                } else {
+                    Some(*index as usize)
+                };
+                if let Some(i) = resolved
+                    && let Some(v) = list.get(i)
+                {
+                    out.push((path.child(PathSeg::Index(i)), Cursor::Node(v)));
+                }
+            }
+        }
+        Step::Filter(pred) => {
+            for (seg, v) in cursor.children() {
+                if pred.test(v) {
+                    out.push((path.child(seg), Cursor::Node(v)));
+                }
+            }
+        }
+    }
+}
+
+fn descend<'a>(path: &SecPath, cursor: Cursor<'a>, out: &mut Vec<(SecPath, Cursor<'a>)>) {
+    out.push((path.clone(), cursor));
+    for (seg, v) in cursor.children() {
+        descend(&path.child(seg), Cursor::Node(v), out);
+    }
+}
+
+/// 大小写不敏感的通配匹配，`*` 匹配任意长度，`?` 匹配单个字符。
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.to_uppercase().chars().collect();
+    let t: Vec<char> = text.to_uppercase().chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+struct Parser<'s> {
+    expr: &'s str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn new(expr: &'s str) -> Self {
+        Self {
+            expr,
+            chars: expr.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn error(&self, msg: &str) -> SecError {
+        OrionSecReason::from_validation()
+            .to_err()
+            .with_detail(format!("query `{}` at {}: {}", self.expr, self.pos, msg))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse(mut self) -> SecResult<Vec<Step>> {
+        let mut steps = Vec::new();
+        if self.chars.is_empty() {
+            return Err(self.error("empty query"));
+        }
+        if self.peek() != Some('[') {
+            steps.push(self.segment()?);
+        }
+        while let Some(c) = self.peek() {
+            match c {
+                '.' => {
+                    self.pos += 1;
+                    steps.push(self.segment()?);
+                }
+                '[' => {
+                    self.pos += 1;
+                    steps.push(self.selector()?);
+                    if !self.eat(']') {
+                        return Err(self.error("expect `]`"));
+                    }
+                }
+                _ => return Err(self.error("expect `.` or `[`")),
+            }
+        }
+        Ok(steps)
+    }
+
+    fn segment(&mut self) -> SecResult<Step> {
+        match self.peek() {
+            Some('"') | Some('\'') => Ok(Step::Key(UniString::from(self.quoted()?))),
+            _ => {
+                let start = self.pos;
+                while let Some(c) = self.peek() {
+                    if matches!(c, '.' | '[' | ']') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                let word: String = self.chars[start..self.pos].iter().collect();
+                let word = word.trim();
+                match word {
+                    "" => Err(self.error("empty key")),
+                    "*" => Ok(Step::Wildcard),
+                    "**" => Ok(Step::Descend),
+                    w if w.contains(['*', '?']) => Ok(Step::Glob(w.to_string())),
+                    w => Ok(Step::Key(UniString::from(w))),
+                }
+            }
+        }
+    }
+
+    fn selector(&mut self) -> SecResult<Step> {
+        match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                Ok(Step::Wildcard)
+            }
+            Some('"') | Some('\'') => Ok(Step::Key(UniString::from(self.quoted()?))),
+            Some('?') => {
+                self.pos += 1;
+                self.predicate()
+            }
+            _ => {
+                let text = self.until(&[']']);
+                text.trim()
+                    .parse::<i64>()
+                    .map(Step::Index)
+                    .map_err(|_| self.error("invalid index"))
+            }
+        }
+    }
+
+    fn predicate(&mut self) -> SecResult<Step> {
+        let path = self.until(&['=', '!', ']']).trim().to_string();
+        if path.is_empty() {
+            return Err(self.error("empty predicate"));
+        }
+        let cond = if self.eat('!') {
+            if !self.eat('=') {
+                return Err(self.error("expect `!=`"));
+            }
+            Some((false, self.literal()?))
+        } else if self.eat('=') {
+            self.eat('=');
+            Some((true, self.literal()?))
+        } else {
+            None
+        };
+        Ok(Step::Filter(Predicate { path, cond }))
+    }
+
+    fn literal(&mut self) -> SecResult<String> {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some('"') | Some('\'') => {
+                let lit = self.quoted()?;
+                while self.peek().is_some_and(char::is_whitespace) {
+                    self.pos += 1;
+                }
+                Ok(lit)
+            }
+            _ => Ok(self.until(&[']']).trim().to_string()),
+        }
+    }
+
+    fn until(&mut self, stops: &[char]) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if stops.contains(&c) {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn quoted(&mut self) -> SecResult<String> {
+        let Some(quote) = self.peek() else {
+            return Err(self.error("expect quote"));
+        };
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated quote")),
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(c) => out.push(c),
+                        None => return Err(self.error("unterminated escape")),
+                    }
+                    self.pos += 1;
+                }
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                Some(c) => {
+                    out.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::SecFrom;
+    use crate::types::UniCaseMap;
+
+    fn server(name: &str, password: &str) -> SecValueType {
+        let mut obj = UniCaseMap::new();
+        obj.insert("name".into(), SecValueType::nor_from(name.to_string()));
+        obj.insert(
+            "password".into(),
+            SecValueType::nor_from(password.to_string()),
+        );
+        SecValueType::from(obj)
+    }
+
+    fn sample() -> SecValueObj {
+        let mut db = UniCaseMap::new();
+        db.insert("user".into(), SecValueType::nor_from("root".to_string()));
+        db.insert(
+            "password".into(),
+            SecValueType::nor_from("db-pass".to_string()),
+        );
+        db.insert("api_token".into(), SecValueType::nor_from("t1".to_string()));
+
+        let mut root = UniCaseMap::new();
+        root.insert("db".into(), SecValueType::from(db));
+        root.insert(
+            "servers".into(),
+            SecValueType::from(vec![server("prod", "p1"), server("dev", "p2")]),
+        );
+        root.insert("a.b".into(), SecValueType::nor_from(1u64));
+        root.insert("token".into(), SecValueType::nor_from("t0".to_string()));
+        root
+    }
+
+    fn paths(query: &str, obj: &SecValueObj) -> Vec<String> {
+        SecQuery::parse(query)
+            .unwrap()
+            .select_paths(obj)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_query_plain_and_index() {
+        let obj = sample();
+        assert_eq!(paths("db.user", &obj), vec!["DB.USER"]);
+        assert_eq!(paths("servers[0].name", &obj), vec!["SERVERS[0].NAME"]);
+        assert_eq!(paths("servers[-1].name", &obj), vec!["SERVERS[1].NAME"]);
+        assert!(paths("servers[-3]", &obj).is_empty());
+        assert!(paths("db.missing", &obj).is_empty());
+    }
+
+    #[test]
+    fn test_query_wildcards() {
+        let obj = sample();
+        assert_eq!(
+            paths("servers[*].password", &obj),
+            vec!["SERVERS[0].PASSWORD", "SERVERS[1].PASSWORD"]
+        );
+        assert_eq!(paths("*.password", &obj), vec!["DB.PASSWORD"]);
+        assert_eq!(paths("db.*_token", &obj), vec!["DB.API_TOKEN"]);
+    }
+
+    #[test]
+    fn test_query_recursive_descent() {
+        let obj = sample();
+        assert_eq!(
+            paths("**.password", &obj),
+            vec!["DB.PASSWORD", "SERVERS[0].PASSWORD", "SERVERS[1].PASSWORD"]
+        );
+        assert_eq!(paths("**.*token", &obj), vec!["TOKEN", "DB.API_TOKEN"]);
+        assert_eq!(paths("**.**.user", &obj), vec!["DB.USER"]);
+    }
+
+    #[test]
+    fn test_query_quoted_keys() {
+        let obj = sample();
+        assert_eq!(paths("\"a.b\"", &obj), vec!["\"A.B\""]);
+        assert_eq!(paths("['a.b']", &obj), vec!["\"A.B\""]);
+        let path = SecQuery::parse("\"a.b\"").unwrap().select_paths(&obj)[0].clone();
+        let reparsed = SecQuery::parse(&path.to_string()).unwrap();
+        assert_eq!(reparsed.select_paths(&obj), vec![path]);
+    }
+
+    #[test]
+    fn test_query_predicates() {
+        let obj = sample();
+        assert_eq!(
+            paths("servers[?name=prod].password", &obj),
+            vec!["SERVERS[0].PASSWORD"]
+        );
+        assert_eq!(
+            paths("servers[?name != 'prod'].password", &obj),
+            vec!["SERVERS[1].PASSWORD"]
+        );
+        assert_eq!(paths("servers[?name]", &obj).len(), 2);
+        assert!(paths("servers[?missing]", &obj).is_empty());
+    }
+
+    #[test]
+    fn test_query_mark_secret() {
+        let mut obj = sample();
+        let query = SecQuery::parse("**.password").unwrap();
+        assert_eq!(query.mark_secret(&mut obj), 3);
+        for m in query.select(&obj) {
+            assert!(m.value.is_secret(), "{} should be secret", m.path);
+        }
+        assert!(
+            matches!(obj.value_get("db.user"), Some(SecValueType::String(v)) if !v.is_secret())
+        );
+    }
+
+    #[test]
+    fn test_query_mark_public_inside_secret_container() {
+        let mut obj = sample();
+        SecQuery::parse("db").unwrap().mark_secret(&mut obj);
+        assert!(obj.value_get("db").unwrap().is_secret());
+
+        SecQuery::parse("db.user").unwrap().mark_public(&mut obj);
+        assert!(!obj.value_get("db").unwrap().is_secret());
+        assert!(!obj.value_get("db.user").unwrap().is_secret());
+        assert!(obj.value_get("db.password").unwrap().is_secret());
+        assert!(obj.value_get("db.api_token").unwrap().is_secret());
+    }
+
+    #[test]
+    fn test_query_parse_errors() {
+        assert!(SecQuery::parse("").is_err());
+        assert!(SecQuery::parse("a..b").is_err());
+        assert!(SecQuery::parse("a[abc]").is_err());
+        assert!(SecQuery::parse("a[0").is_err());
+        assert!(SecQuery::parse("'unterminated").is_err());
+        assert!("servers[*]".parse::<SecQuery>().is_ok());
+    }
+
+    #[test]
+    fn test_sec_path_from_str() {
+        let path: SecPath = "a.\"b.c\"[2].d".parse().unwrap();
+        assert_eq!(path.to_string(), "A.\"B.C\"[2].D");
+        assert_eq!(path.len(), 4);
+        assert!("a.*".parse::<SecPath>().is_err());
+        assert!("a[-1]".parse::<SecPath>().is_err());
+        assert!("a[?x]".parse::<SecPath>().is_err());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*password*", "DB_PASSWORD_FILE"));
+        assert!(wildcard_match("*_key", "API_KEY"));
+        assert!(wildcard_match("t?ken", "TOKEN"));
+        assert!(!wildcard_match("*_key", "KEYRING"));
+    }
+}
@@ -0,0 +1,51 @@
+use orion_error::ErrorOwe;
+
+use crate::error::SecResult;
+
+/// 用操作系统 CSPRNG 填充缓冲区。
+pub(crate) fn fill(buf: &mut [u8]) -> SecResult<()> {
+    getrandom::fill(buf).owe_sys()
+}
+
+/// `[0, n)` 上的均匀随机数，拒绝采样避免取模偏差。
+pub(crate) fn below(n: usize) -> SecResult<usize> {
+    debug_assert!(n > 0);
+    let n = n as u64;
+    let zone = u64::MAX - u64::MAX % n;
+    loop {
+        let v = getrandom::u64().owe_sys()?;
+        if v < zone {
+            return Ok((v % n) as usize);
+        }
+    }
+}
+
+pub(crate) fn pick<T: Copy>(items: &[T]) -> SecResult<T> {
+    Ok(items[below(items.len())?])
+}
+
+/// Fisher–Yates 洗牌。
+pub(crate) fn shuffle<T>(items: &mut [T]) -> SecResult<()> {
+    for i in (1..items.len()).rev() {
+        items.swap(i, below(i + 1)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_below_and_shuffle() {
+        for n in [1, 2, 7, 100] {
+            for _ in 0..50 {
+                assert!(below(n).unwrap() < n);
+            }
+        }
+        let mut items: Vec<u32> = (0..32).collect();
+        shuffle(&mut items).unwrap();
+        items.sort();
+        assert_eq!(items, (0..32).collect::<Vec<_>>());
+    }
+}
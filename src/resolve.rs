@@ -0,0 +1,366 @@
+use std::{env, fs, path::Path, process::Command};
+
+use log::warn;
+use orion_error::{ErrorOwe, ErrorWith, ToStructError, UvsFrom};
+
+use crate::{
+    error::{OrionSecReason, SecError, SecResult},
+    query::{PathSeg, SecPath},
+    sec::{SecFrom, SecValueObj, SecValueType},
+    visit::{SecVisitorMut, visit_mut, visit_value_mut},
+};
+
+/// 把 `scheme://target` 形式的引用解析为真实取值的后端。
+pub trait SecResolver: Send + Sync {
+    /// 处理的 scheme，不含 `://`，例如 `file`。
+    fn scheme(&self) -> &str;
+    fn resolve(&self, target: &str) -> SecResult<String>;
+}
+
+/// `file:///run/secrets/db`：读取文件内容，去掉结尾的一个换行。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FileResolver;
+
+impl SecResolver for FileResolver {
+    fn scheme(&self) -> &str {
+        "file"
+    }
+
+    fn resolve(&self, target: &str) -> SecResult<String> {
+        let path = Path::new(target);
+        let content = fs::read_to_string(path).owe_sys().with(path)?;
+        Ok(trim_newline(content))
+    }
+}
+
+/// `env://DB_PASS`：读取进程环境变量。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvResolver;
+
+impl SecResolver for EnvResolver {
+    fn scheme(&self) -> &str {
+        "env"
+    }
+
+    fn resolve(&self, target: &str) -> SecResult<String> {
+        env::var(target).map_err(|_| resolve_error(format!("env {target} not set")))
+    }
+}
+
+/// `exec://pass show db` 或 `exec://["pass", "show", "db name"]`：直接执行（不经过 shell），取标准输出。
+///
+/// 参数要么是 JSON 字符串数组，要么是按空白拆分的简单单词；
+/// 含引号、反斜杠或 shell 元字符的字符串会被拒绝，而不是被错误拆分。
+/// 命令以非零状态退出时返回错误，错误中不包含命令输出。
+///
+/// 会执行密文文件中写明的任意命令，不在 [`SecResolverSet::default`] 中，需显式注册。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExecResolver;
+
+impl SecResolver for ExecResolver {
+    fn scheme(&self) -> &str {
+        "exec"
+    }
+
+    fn resolve(&self, target: &str) -> SecResult<String> {
+        let argv = exec_argv(target)?;
+        let Some((program, args)) = argv.split_first() else {
+            return Err(resolve_error("empty exec reference".to_string()));
+        };
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .owe_sys()
+            .with(program.as_str())?;
+        if !output.status.success() {
+            return Err(resolve_error(format!(
+                "command `{program}` exited with {}",
+                output.status
+            )));
+        }
+        let stdout = String::from_utf8(output.stdout)
+            .map_err(|_| resolve_error(format!("command `{program}` output is not utf-8")))?;
+        Ok(trim_newline(stdout))
+    }
+}
+
+fn exec_argv(target: &str) -> SecResult<Vec<String>> {
+    let target = target.trim();
+    if target.starts_with('[') {
+        return serde_json::from_str(target)
+            .map_err(|_| resolve_error("exec argv must be a JSON array of strings".to_string()));
+    }
+    if let Some(c) = target.chars().find(|c| {
+        matches!(
+            c,
+            '\\' | '\''
+                | '"'
+                | '`'
+                | '$'
+                | ';'
+                | '|'
+                | '&'
+                | '<'
+                | '>'
+                | '('
+                | ')'
+                | '*'
+                | '?'
+                | '~'
+        )
+    }) {
+        return Err(resolve_error(format!(
+            "exec reference contains shell syntax `{c}`, use a JSON argv list"
+        )));
+    }
+    Ok(target.split_whitespace().map(str::to_string).collect())
+}
+
+/// 一组按 scheme 分派的解析器。
+///
+/// 默认只包含 `file`、`env` 两个内置后端，`exec` 需用 `with(ExecResolver)` 显式开启；
+/// 后注册的同名 scheme 覆盖先注册的。
+/// 未注册 scheme 的字符串（如 `https://...`）原样保留。
+pub struct SecResolverSet {
+    resolvers: Vec<Box<dyn SecResolver>>,
+}
+
+impl Default for SecResolverSet {
+    fn default() -> Self {
+        Self::empty().with(FileResolver).with(EnvResolver)
+    }
+}
+
+impl SecResolverSet {
+    pub fn empty() -> Self {
+        Self {
+            resolvers: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with<R: SecResolver + 'static>(mut self, resolver: R) -> Self {
+        self.resolvers.retain(|r| r.scheme() != resolver.scheme());
+        self.resolvers.push(Box::new(resolver));
+        self
+    }
+
+    pub fn schemes(&self) -> Vec<&str> {
+        self.resolvers.iter().map(|r| r.scheme()).collect()
+    }
+
+    /// 若 `text` 是已注册 scheme 的引用则解析并返回 `Some`，否则返回 `None`。
+    pub fn resolve_str(&self, text: &str) -> SecResult<Option<String>> {
+        let Some((scheme, target)) = text.trim().split_once("://") else {
+            return Ok(None);
+        };
+        match self.resolvers.iter().find(|r| r.scheme() == scheme) {
+            Some(resolver) => resolver.resolve(target).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 解析 `obj` 中全部引用，替换为密文字符串，返回解析的个数。
+    pub fn apply(&self, obj: &mut SecValueObj) -> SecResult<usize> {
+        let mut resolving = Resolving::new(self, SecPath::new());
+        visit_mut(obj, &mut resolving);
+        resolving.finish()
+    }
+
+    /// 解析单个取值（含其子节点），`key` 用于错误信息中的路径。
+    pub fn apply_value(&self, key: &str, value: &mut SecValueType) -> SecResult<usize> {
+        let base = SecPath::from(vec![PathSeg::Key(key.into())]);
+        let mut resolving = Resolving::new(self, base);
+        visit_value_mut(value, &mut resolving);
+        resolving.finish()
+    }
+}
+
+struct Resolving<'a> {
+    set: &'a SecResolverSet,
+    base: SecPath,
+    count: usize,
+    error: Option<SecError>,
+}
+
+impl<'a> Resolving<'a> {
+    fn new(set: &'a SecResolverSet, base: SecPath) -> Self {
+        Self {
+            set,
+            base,
+            count: 0,
+            error: None,
+        }
+    }
+
+    fn finish(self) -> SecResult<usize> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.count),
+        }
+    }
+}
+
+impl SecVisitorMut for Resolving<'_> {
+    fn visit_leaf_mut(&mut self, path: &SecPath, value: &mut SecValueType) {
+        if self.error.is_some() {
+            return;
+        }
+        let SecValueType::String(text) = value else {
+            return;
+        };
+        match self.set.resolve_str(text.value()) {
+            Ok(Some(resolved)) => {
+                let meta = value.meta().cloned();
+                *value = SecValueType::sec_from(resolved);
+                value.set_meta(meta);
+                self.count += 1;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                let mut full = self.base.clone();
+                for seg in path.segments() {
+                    full.push(seg.clone());
+                }
+                warn!(target: "exec", "  unresolved secret reference at {}", full);
+                self.error = Some(err.with_detail(format!("unresolved reference at {full}")));
+            }
+        }
+    }
+}
+
+fn trim_newline(mut text: String) -> String {
+    if text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
+    }
+    text
+}
+
+fn resolve_error(detail: String) -> SecError {
+    OrionSecReason::from_validation()
+        .to_err()
+        .with_detail(detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::ValueGetter;
+    use crate::types::UniCaseMap;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    struct Fixed;
+
+    impl SecResolver for Fixed {
+        fn scheme(&self) -> &str {
+            "vault"
+        }
+        fn resolve(&self, target: &str) -> SecResult<String> {
+            Ok(format!("v:{target}"))
+        }
+    }
+
+    fn nor(v: &str) -> SecValueType {
+        SecValueType::nor_from(v.to_string())
+    }
+
+    #[test]
+    fn test_resolve_builtin_backends() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "from-file").unwrap();
+        let key = "ORION_SEC_RESOLVE_TEST";
+        let _lock = crate::env::env_lock();
+        unsafe {
+            env::set_var(key, "from-env");
+        }
+
+        let mut obj = UniCaseMap::new();
+        obj.insert(
+            "A".into(),
+            nor(&format!("file://{}", file.path().display())),
+        );
+        obj.insert("B".into(), nor(&format!("env://{key}")));
+        obj.insert("C".into(), nor("exec://echo from exec"));
+        obj.insert("D".into(), nor("https://example.com"));
+        obj.insert("E".into(), nor(r#"exec://["printf", "%s", "a 'b'"]"#));
+        let count = SecResolverSet::default().with(ExecResolver).apply(&mut obj);
+        unsafe {
+            env::remove_var(key);
+        }
+
+        assert_eq!(count.unwrap(), 4);
+        let sec = |v: &str| SecValueType::sec_from(v.to_string());
+        assert_eq!(obj.value_get("A"), Some(sec("from-file")));
+        assert_eq!(obj.value_get("B"), Some(sec("from-env")));
+        assert_eq!(obj.value_get("C"), Some(sec("from exec")));
+        assert_eq!(obj.value_get("D"), Some(nor("https://example.com")));
+        assert_eq!(obj.value_get("E"), Some(sec("a 'b'")));
+    }
+
+    #[test]
+    fn test_resolve_exec_is_opt_in_and_rejects_shell_syntax() {
+        let mut obj = UniCaseMap::new();
+        obj.insert(
+            "CMD".into(),
+            nor("exec://touch /tmp/orion-sec-should-not-run"),
+        );
+        assert_eq!(SecResolverSet::default().apply(&mut obj).unwrap(), 0);
+        assert_eq!(
+            obj.value_get("CMD"),
+            Some(nor("exec://touch /tmp/orion-sec-should-not-run"))
+        );
+
+        let exec = SecResolverSet::empty().with(ExecResolver);
+        for target in [
+            "exec://echo 'a b'",
+            "exec://echo $HOME",
+            "exec://echo a; rm x",
+            "exec://[\"echo\", 1]",
+        ] {
+            let mut obj = UniCaseMap::new();
+            obj.insert("CMD".into(), nor(target));
+            assert!(exec.apply(&mut obj).is_err(), "{target}");
+        }
+    }
+
+    #[test]
+    fn test_resolve_custom_backend_in_nested_value() {
+        let set = SecResolverSet::empty().with(Fixed);
+        assert_eq!(set.schemes(), vec!["vault"]);
+        let mut value = SecValueType::from(vec![nor("vault://db/pass"), nor("file:///x")]);
+        assert_eq!(set.apply_value("SEC_LIST", &mut value).unwrap(), 1);
+        let SecValueType::List(list) = value else {
+            panic!("expected list");
+        };
+        assert_eq!(
+            list.value()[0],
+            SecValueType::sec_from("v:db/pass".to_string())
+        );
+        assert_eq!(list.value()[1], nor("file:///x"));
+    }
+
+    #[test]
+    fn test_resolve_failures_report_path() {
+        let mut obj = UniCaseMap::new();
+        obj.insert("SEC_DB".into(), nor("env://ORION_SEC_RESOLVE_MISSING_VAR"));
+        let msg = format!("{}", SecResolverSet::default().apply(&mut obj).unwrap_err());
+        assert!(msg.contains("SEC_DB"));
+
+        let mut obj = UniCaseMap::new();
+        obj.insert("SEC_CMD".into(), nor("exec://false"));
+        assert!(
+            SecResolverSet::default()
+                .with(ExecResolver)
+                .apply(&mut obj)
+                .is_err()
+        );
+
+        let mut obj = UniCaseMap::new();
+        obj.insert("SEC_F".into(), nor("file:///nonexistent/orion/sec"));
+        assert!(SecResolverSet::default().apply(&mut obj).is_err());
+    }
+}
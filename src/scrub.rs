@@ -0,0 +1,385 @@
+use std::{
+    io::Read,
+    process::{Command, ExitStatus, Stdio},
+    sync::mpsc,
+    thread,
+};
+
+use orion_error::ErrorOwe;
+use regex::bytes::Regex;
+
+use crate::{
+    audit::SecretScalars,
+    error::SecResult,
+    inject::SecInjector,
+    sec::{SECRET_MASK, SecValueObj},
+    visit::visit,
+};
+
+/// 短于该长度的密文不参与遮蔽，避免输出被大面积替换。
+pub const MIN_SCRUB_LEN: usize = 4;
+/// 无换行时缓冲的上限，超过后输出不可能与密文重叠的前缀。
+const MAX_PENDING: usize = 8 * 1024;
+
+/// 把文本中出现的密文替换为 `SECRET_MASK`。
+///
+/// 多个密文重叠时优先匹配较长者；多行密文（如 PEM 私钥）按行分别匹配。
+#[derive(Debug, Clone)]
+pub struct SecScrubber {
+    pattern: Option<Regex>,
+    max_len: usize,
+}
+
+impl SecScrubber {
+    pub fn new<I, S>(secrets: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut needles: Vec<String> = secrets
+            .into_iter()
+            .flat_map(|s| {
+                s.as_ref()
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| l.len() >= MIN_SCRUB_LEN)
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        needles.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        needles.dedup();
+        let max_len = needles.first().map_or(0, String::len);
+        let pattern = (!needles.is_empty()).then(|| {
+            let alt = needles
+                .iter()
+                .map(|n| regex::escape(n))
+                .collect::<Vec<_>>()
+                .join("|");
+            Regex::new(&alt).expect("escaped secrets form a valid pattern")
+        });
+        Self { pattern, max_len }
+    }
+
+    /// 取对象中全部密文叶子（含密文容器下的叶子）。
+    pub fn from_obj(obj: &SecValueObj) -> Self {
+        let mut collect = SecretScalars::default();
+        visit(obj, &mut collect);
+        Self::new(collect.found.into_iter().map(|(_, v)| v))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pattern.is_none()
+    }
+
+    pub fn scrub(&self, text: &str) -> String {
+        String::from_utf8_lossy(&self.scrub_bytes(text.as_bytes())).into_owned()
+    }
+
+    fn scrub_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        match &self.pattern {
+            Some(re) => re.replace_all(bytes, SECRET_MASK.as_bytes()).into_owned(),
+            None => bytes.to_vec(),
+        }
+    }
+
+    /// 创建流式遮蔽器，处理跨越读取边界的密文。
+    pub fn stream(&self) -> ScrubStream<'_> {
+        ScrubStream {
+            scrubber: self,
+            pending: Vec::new(),
+        }
+    }
+}
+
+/// 逐块输入、逐行输出的遮蔽器。
+///
+/// 输出片段通常是带换行的完整行；单行超过缓冲上限时，
+/// 先输出不会与未完整读到的密文重叠的前缀。
+#[derive(Debug)]
+pub struct ScrubStream<'a> {
+    scrubber: &'a SecScrubber,
+    pending: Vec<u8>,
+}
+
+impl ScrubStream<'_> {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.pending.extend_from_slice(chunk);
+        let mut out = Vec::new();
+        while let Some(nl) = self.pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=nl).collect();
+            out.push(self.emit(&line));
+        }
+        if self.pending.len() > MAX_PENDING {
+            let cut = self.safe_cut();
+            if cut > 0 {
+                let head: Vec<u8> = self.pending.drain(..cut).collect();
+                out.push(self.emit(&head));
+            }
+        }
+        out
+    }
+
+    /// 输出剩余内容（最后一行可能没有换行）。
+    pub fn finish(self) -> Option<String> {
+        (!self.pending.is_empty()).then(|| self.emit(&self.pending))
+    }
+
+    fn emit(&self, bytes: &[u8]) -> String {
+        String::from_utf8_lossy(&self.scrubber.scrub_bytes(bytes)).into_owned()
+    }
+
+    /// 未读完的密文只可能始于末尾 `max_len - 1` 字节内；
+    /// 再向前退到字符边界，并避开跨越切点的完整匹配。
+    fn safe_cut(&self) -> usize {
+        let buf = &self.pending;
+        let mut cut = buf.len() - self.scrubber.max_len.saturating_sub(1).min(buf.len());
+        while cut > 0 && cut < buf.len() && (buf[cut] & 0xC0) == 0x80 {
+            cut -= 1;
+        }
+        if let Some(re) = &self.scrubber.pattern
+            && let Some(m) = re.find_iter(buf).find(|m| m.start() < cut && m.end() > cut)
+        {
+            cut = m.start();
+        }
+        cut
+    }
+}
+
+/// 输出来源。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// 遮蔽后的子进程输出。
+#[derive(Debug)]
+pub struct MaskedOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 注入密文运行子进程，并遮蔽其标准输出与标准错误中回显的密文。
+///
+/// 遮蔽器由注入的密文变量构建，因此只会遮蔽实际交给子进程的取值。
+#[derive(Debug, Clone, Default)]
+pub struct SecRunner {
+    injector: SecInjector,
+}
+
+impl SecRunner {
+    pub fn new(injector: SecInjector) -> Self {
+        Self { injector }
+    }
+
+    pub fn run(&self, cmd: &mut Command, obj: &SecValueObj) -> SecResult<MaskedOutput> {
+        self.run_with(cmd, obj, |_, _| {})
+    }
+
+    /// 同 [`SecRunner::run`]，每输出一整行（已遮蔽、去掉换行）回调一次，
+    /// 最后一行没有换行时在子进程关闭输出后回调。
+    ///
+    /// 读取线程在交付密文之前启动，子进程先大量输出再读取标准输入也不会死锁。
+    pub fn run_with<F>(
+        &self,
+        cmd: &mut Command,
+        obj: &SecValueObj,
+        mut on_line: F,
+    ) -> SecResult<MaskedOutput>
+    where
+        F: FnMut(OutputStream, &str),
+    {
+        let scrubber = SecScrubber::new(
+            self.injector
+                .vars(obj)?
+                .into_iter()
+                .filter(|v| v.secret)
+                .map(|v| v.value),
+        );
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        let payload = self.injector.apply(cmd, obj)?;
+        let mut child = cmd.spawn().owe_sys()?;
+        let readers: Vec<(OutputStream, Box<dyn Read + Send>)> = [
+            child
+                .stdout
+                .take()
+                .map(|r| (OutputStream::Stdout, Box::new(r) as Box<dyn Read + Send>)),
+            child
+                .stderr
+                .take()
+                .map(|r| (OutputStream::Stderr, Box::new(r) as Box<dyn Read + Send>)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        let (deliver_result, read_result) = thread::scope(|s| {
+            let (tx, rx) = mpsc::channel();
+            let handles: Vec<_> = readers
+                .into_iter()
+                .map(|(stream, mut reader)| {
+                    let tx = tx.clone();
+                    let scrubber = &scrubber;
+                    s.spawn(move || -> std::io::Result<()> {
+                        let mut scrub = scrubber.stream();
+                        let mut buf = [0u8; 4096];
+                        loop {
+                            let n = reader.read(&mut buf)?;
+                            if n == 0 {
+                                break;
+                            }
+                            for piece in scrub.push(&buf[..n]) {
+                                let _ = tx.send((stream, piece));
+                            }
+                        }
+                        if let Some(piece) = scrub.finish() {
+                            let _ = tx.send((stream, piece));
+                        }
+                        Ok(())
+                    })
+                })
+                .collect();
+            drop(tx);
+            let deliver_result = payload.deliver(&mut child);
+            let mut partial = [String::new(), String::new()];
+            for (stream, piece) in rx {
+                let (out, line) = match stream {
+                    OutputStream::Stdout => (&mut stdout, &mut partial[0]),
+                    OutputStream::Stderr => (&mut stderr, &mut partial[1]),
+                };
+                out.push_str(&piece);
+                line.push_str(&piece);
+                if line.ends_with('\n') {
+                    on_line(stream, line.trim_end_matches(['\n', '\r']));
+                    line.clear();
+                }
+            }
+            for (stream, line) in [OutputStream::Stdout, OutputStream::Stderr]
+                .into_iter()
+                .zip(partial)
+            {
+                if !line.is_empty() {
+                    on_line(stream, line.trim_end_matches('\r'));
+                }
+            }
+            let read_result = handles
+                .into_iter()
+                .try_for_each(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)));
+            (deliver_result, read_result)
+        });
+        let status = child.wait().owe_sys()?;
+        deliver_result?;
+        read_result.owe_sys()?;
+        Ok(MaskedOutput {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inject::SecDelivery;
+    use crate::sec::{SecFrom, SecValueType};
+
+    #[test]
+    fn test_scrub_stream_split_secrets() {
+        let scrubber = SecScrubber::new(["hunter22", "hunter22-long", "abc", "line1\nline2-pem"]);
+        assert_eq!(
+            scrubber.scrub("a hunter22-long b hunter22 abc line2-pem"),
+            format!("a {m} b {m} abc {m}", m = SECRET_MASK)
+        );
+
+        let mut stream = scrubber.stream();
+        let mut out = Vec::new();
+        for chunk in ["pw=hun", "ter", "22\nnext hunter2", "2-lo", "ng end"] {
+            out.extend(stream.push(chunk.as_bytes()));
+        }
+        out.extend(stream.finish());
+        assert_eq!(
+            out.concat(),
+            format!("pw={SECRET_MASK}\nnext {SECRET_MASK} end")
+        );
+
+        let mut stream = scrubber.stream();
+        let mut long = "x".repeat(MAX_PENDING - 3);
+        long.push_str("hunter");
+        let mut out = stream.push(long.as_bytes());
+        assert_eq!(out.len(), 1);
+        out.extend(stream.push(b"22-long tail"));
+        out.extend(stream.finish());
+        let text = out.concat();
+        assert!(text.ends_with(&format!("{SECRET_MASK} tail")));
+        assert!(!text.contains("hunter"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_runner_masks_output() {
+        let mut obj = SecValueObj::new();
+        obj.insert(
+            "TOKEN".into(),
+            SecValueType::sec_from("t0k3n-value".to_string()),
+        );
+        obj.insert(
+            "USER".into(),
+            SecValueType::nor_from("app-user".to_string()),
+        );
+        assert_eq!(
+            SecScrubber::from_obj(&obj).scrub("t0k3n-value app-user"),
+            format!("{SECRET_MASK} app-user")
+        );
+        let mut lines = Vec::new();
+        let out = SecRunner::default()
+            .run_with(
+                Command::new("sh").arg("-c").arg(
+                    r#"echo "user=$SEC_USER token=$SEC_TOKEN"; printf 'bad %s' "$SEC_TOKEN" >&2; exit 3"#,
+                ),
+                &obj,
+                |stream, line| lines.push((stream, line.to_string())),
+            )
+            .unwrap();
+        assert_eq!(out.status.code(), Some(3));
+        assert_eq!(out.stdout, format!("user=app-user token={SECRET_MASK}\n"));
+        assert_eq!(out.stderr, format!("bad {SECRET_MASK}"));
+        assert!(lines.contains(&(
+            OutputStream::Stdout,
+            format!("user=app-user token={SECRET_MASK}")
+        )));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_runner_chatty_child_with_stdin_delivery() {
+        let secret = "s".repeat(200 * 1024);
+        let mut obj = SecValueObj::new();
+        obj.insert("BIG".into(), SecValueType::sec_from(secret));
+        let runner = SecRunner::new(SecInjector::new().with_delivery(SecDelivery::Stdin));
+        let mut lines = Vec::new();
+        let out = runner
+            .run_with(
+                Command::new("sh").arg("-c").arg(
+                    r#"i=0; while [ $i -lt 4000 ]; do echo "line $i ................................................"; i=$((i+1)); done; head -c 30000 /dev/zero | tr '\0' x; echo; wc -c"#,
+                ),
+                &obj,
+                |stream, line| lines.push((stream, line.len())),
+            )
+            .unwrap();
+        assert!(out.status.success());
+        assert_eq!(lines.len(), 4002);
+        assert_eq!(lines[4000], (OutputStream::Stdout, 30000));
+        assert_eq!(out.stdout.lines().last().unwrap().trim(), "204814");
+
+        let out = runner
+            .run(Command::new("sh").arg("-c").arg("echo done; exit 3"), &obj)
+            .unwrap();
+        assert_eq!(out.status.code(), Some(3));
+        assert_eq!(out.stdout, "done\n");
+    }
+}
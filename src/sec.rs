@@ -1,4 +1,5 @@
 use std::{
+    cell::Cell,
     fmt::{Display, Formatter},
     net::IpAddr,
 };
@@ -6,30 +7,139 @@ use std::{
 use derive_more::From;
 use indexmap::IndexMap;
 use orion_variate::vars::ValueType;
+use serde::{Serializer, ser::SerializeStruct};
 use serde_derive::{Deserialize, Serialize};
 use unicase::UniCase;
 
-use crate::types::{UniCaseMap, UniString};
+use chrono::{DateTime, Utc};
 
-const SECRET_MASK: &str = "********";
+use crate::{
+    error::SecResult,
+    meta::SecMeta,
+    types::{UniCaseMap, UniString},
+};
+
+pub(crate) const SECRET_MASK: &str = "********";
 
 pub trait ToUniCase<T> {
     fn to_unicase(self) -> UniCase<T>;
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+/// 带密级标记的取值。
+///
+/// 序列化时密文取值输出为掩码；需要持久化真实取值时用 [`SecPlain`] 显式包装。
+#[derive(Clone, Deserialize, PartialEq)]
 pub struct SecValue<T> {
     is_secret: bool,
     value: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    meta: Option<Box<SecMeta>>,
 }
 impl<T> SecValue<T> {
     pub fn value(&self) -> &T {
         &self.value
     }
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+    pub fn into_value(self) -> T {
+        self.value
+    }
     pub fn is_secret(&self) -> bool {
         self.is_secret
     }
+    pub fn set_secret(&mut self, is_secret: bool) {
+        self.is_secret = is_secret;
+    }
+    pub fn meta(&self) -> Option<&SecMeta> {
+        self.meta.as_deref()
+    }
+    pub fn set_meta(&mut self, meta: Option<SecMeta>) {
+        self.meta = meta.map(Box::new);
+    }
+    #[must_use]
+    pub fn with_meta(mut self, meta: SecMeta) -> Self {
+        self.set_meta(Some(meta));
+        self
+    }
+    /// 与 [`value`](Self::value) 相同，但在未生效或已过期时返回 `SecReason::Expired`。
+    pub fn checked_value(&self) -> SecResult<&T> {
+        self.checked_value_at(Utc::now())
+    }
+    pub fn checked_value_at(&self, now: DateTime<Utc>) -> SecResult<&T> {
+        if let Some(meta) = &self.meta {
+            meta.check_at(now)?;
+        }
+        Ok(&self.value)
+    }
+}
+impl<T> SecValue<T> {
+    fn map_value<F: FnOnce(T) -> T>(self, f: F) -> Self {
+        Self {
+            is_secret: self.is_secret,
+            value: f(self.value),
+            meta: self.meta,
+        }
+    }
+}
+impl<T> std::fmt::Debug for SecValue<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut dbg = f.debug_struct("SecValue");
+        dbg.field("is_secret", &self.is_secret);
+        if self.is_secret {
+            dbg.field("value", &format_args!("{SECRET_MASK}"));
+        } else {
+            dbg.field("value", &self.value);
+        }
+        if let Some(meta) = &self.meta {
+            dbg.field("meta", meta);
+        }
+        dbg.finish()
+    }
+}
+thread_local! {
+    static PLAINTEXT: Cell<bool> = const { Cell::new(false) };
+}
+
+impl<T: serde::Serialize> serde::Serialize for SecValue<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = if self.meta.is_some() { 3 } else { 2 };
+        let mut st = serializer.serialize_struct("SecValue", len)?;
+        st.serialize_field("is_secret", &self.is_secret)?;
+        if self.is_secret && !PLAINTEXT.get() {
+            st.serialize_field("value", SECRET_MASK)?;
+        } else {
+            st.serialize_field("value", &self.value)?;
+        }
+        match &self.meta {
+            Some(meta) => st.serialize_field("meta", meta)?,
+            None => st.skip_field("meta")?,
+        }
+        st.end()
+    }
+}
+
+/// 以明文序列化其中的全部密文，仅用于需要落盘真实取值的场景（如版本存储）。
+///
+/// 只在当前线程、本次序列化期间生效。
+pub struct SecPlain<'a, T: ?Sized>(pub &'a T);
+
+impl<T: serde::Serialize + ?Sized> serde::Serialize for SecPlain<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Restore(bool);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                PLAINTEXT.set(self.0);
+            }
+        }
+        let _restore = Restore(PLAINTEXT.replace(true));
+        self.0.serialize(serializer)
+    }
 }
+
 impl<T> PartialOrd for SecValue<T>
 where
     T: PartialOrd,
@@ -89,30 +199,34 @@ impl<T> SecConv for UniCaseMap<SecValue<T>> {
 }
 impl SecFrom<IndexMap<String, ValueType>> for SecValueType {
     fn sec_from(value: IndexMap<String, ValueType>) -> Self {
-        SecValueType::Obj(
+        SecValueType::Obj(SecObj::sec_from(
             value
                 .into_iter()
                 .map(|(k, v)| (UniString::from(k), SecValueType::sec_from(v)))
                 .collect(),
-        )
+        ))
     }
 
     fn nor_from(value: IndexMap<String, ValueType>) -> Self {
-        SecValueType::Obj(
+        SecValueType::Obj(SecObj::nor_from(
             value
                 .into_iter()
                 .map(|(k, v)| (UniString::from(k), SecValueType::nor_from(v)))
                 .collect(),
-        )
+        ))
     }
 }
 impl SecFrom<Vec<ValueType>> for SecValueType {
     fn sec_from(value: Vec<ValueType>) -> Self {
-        SecValueType::List(value.into_iter().map(SecValueType::sec_from).collect())
+        SecValueType::List(SecList::sec_from(
+            value.into_iter().map(SecValueType::sec_from).collect(),
+        ))
     }
 
     fn nor_from(value: Vec<ValueType>) -> Self {
-        SecValueType::List(value.into_iter().map(SecValueType::nor_from).collect())
+        SecValueType::List(SecList::nor_from(
+            value.into_iter().map(SecValueType::nor_from).collect(),
+        ))
     }
 }
 impl SecFrom<ValueType> for SecValueType {
@@ -149,12 +263,14 @@ impl<T> SecFrom<T> for SecValue<T> {
         Self {
             is_secret: true,
             value,
+            meta: None,
         }
     }
     fn nor_from(value: T) -> Self {
         Self {
             is_secret: false,
             value,
+            meta: None,
         }
     }
 }
@@ -177,6 +293,8 @@ pub type SecU64 = SecValue<u64>;
 pub type SecF64 = SecValue<f64>;
 pub type SecValueObj = UniCaseMap<SecValueType>;
 pub type SecValueVec = Vec<SecValueType>;
+pub type SecObj = SecValue<SecValueObj>;
+pub type SecList = SecValue<SecValueVec>;
 
 impl Display for SecValueType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -186,11 +304,17 @@ impl Display for SecValueType {
             SecValueType::Number(v) => write!(f, "{v}"),
             SecValueType::Float(v) => write!(f, "{v}"),
             SecValueType::Ip(v) => write!(f, "{v}"),
-            SecValueType::Obj(v) => write!(f, "obj[{}]", v.len()),
-            SecValueType::List(v) => write!(f, "list[{}]", v.len()),
+            SecValueType::Obj(v) if v.is_secret => write!(f, "{}", SECRET_MASK),
+            SecValueType::List(v) if v.is_secret => write!(f, "{}", SECRET_MASK),
+            SecValueType::Obj(v) => write!(f, "obj[{}]", v.value.len()),
+            SecValueType::List(v) => write!(f, "list[{}]", v.value.len()),
         }
     }
 }
+/// 带密级标记的配置值。
+///
+/// `Obj` 与 `List` 容器本身也带有密级：被标记为密文的容器在 `Display`/`Debug`
+/// 中整体显示为掩码，序列化时作为一个密文单元输出（取值为掩码，见 [`SecPlain`]）。
 #[derive(Debug, Clone, PartialEq, From, Serialize, Deserialize)]
 pub enum SecValueType {
     String(SecString),
@@ -198,8 +322,20 @@ pub enum SecValueType {
     Number(SecU64),
     Float(SecF64),
     Ip(SecIpAddr),
-    Obj(SecValueObj),
-    List(SecValueVec),
+    Obj(SecObj),
+    List(SecList),
+}
+
+impl From<SecValueObj> for SecValueType {
+    fn from(value: SecValueObj) -> Self {
+        SecValueType::Obj(SecObj::nor_from(value))
+    }
+}
+
+impl From<SecValueVec> for SecValueType {
+    fn from(value: SecValueVec) -> Self {
+        SecValueType::List(SecList::nor_from(value))
+    }
 }
 
 impl PartialOrd for SecValueType {
@@ -230,6 +366,7 @@ where
 }
 
 impl SecValueType {
+    /// 将节点及其整个子树标记为明文。
     #[must_use]
     pub fn to_nor(self) -> Self {
         match self {
@@ -238,10 +375,11 @@ impl SecValueType {
             SecValueType::Number(v) => Self::Number(v.to_nor()),
             SecValueType::Float(v) => Self::Float(v.to_nor()),
             SecValueType::Ip(v) => Self::Ip(v.to_nor()),
-            SecValueType::Obj(v) => Self::Obj(v.to_nor()),
-            SecValueType::List(v) => Self::List(v.to_nor()),
+            SecValueType::Obj(v) => Self::Obj(v.map_value(SecConv::to_nor).to_nor()),
+            SecValueType::List(v) => Self::List(v.map_value(SecConv::to_nor).to_nor()),
         }
     }
+    /// 将节点及其整个子树标记为密文。
     #[must_use]
     pub fn to_sec(self) -> Self {
         match self {
@@ -250,8 +388,51 @@ impl SecValueType {
             SecValueType::Number(v) => Self::Number(v.to_sec()),
             SecValueType::Float(v) => Self::Float(v.to_sec()),
             SecValueType::Ip(v) => Self::Ip(v.to_sec()),
-            SecValueType::Obj(v) => Self::Obj(v.to_sec()),
-            SecValueType::List(v) => Self::List(v.to_sec()),
+            SecValueType::Obj(v) => Self::Obj(v.map_value(SecConv::to_sec).to_sec()),
+            SecValueType::List(v) => Self::List(v.map_value(SecConv::to_sec).to_sec()),
+        }
+    }
+    /// 节点自身的密级标记；对容器而言即整体是否被标记为密文。
+    pub fn is_secret(&self) -> bool {
+        match self {
+            SecValueType::String(v) => v.is_secret,
+            SecValueType::Bool(v) => v.is_secret,
+            SecValueType::Number(v) => v.is_secret,
+            SecValueType::Float(v) => v.is_secret,
+            SecValueType::Ip(v) => v.is_secret,
+            SecValueType::Obj(v) => v.is_secret,
+            SecValueType::List(v) => v.is_secret,
+        }
+    }
+    /// 节点自身的生命周期元数据。
+    pub fn meta(&self) -> Option<&SecMeta> {
+        match self {
+            SecValueType::String(v) => v.meta(),
+            SecValueType::Bool(v) => v.meta(),
+            SecValueType::Number(v) => v.meta(),
+            SecValueType::Float(v) => v.meta(),
+            SecValueType::Ip(v) => v.meta(),
+            SecValueType::Obj(v) => v.meta(),
+            SecValueType::List(v) => v.meta(),
+        }
+    }
+    pub fn set_meta(&mut self, meta: Option<SecMeta>) {
+        match self {
+            SecValueType::String(v) => v.set_meta(meta),
+            SecValueType::Bool(v) => v.set_meta(meta),
+            SecValueType::Number(v) => v.set_meta(meta),
+            SecValueType::Float(v) => v.set_meta(meta),
+            SecValueType::Ip(v) => v.set_meta(meta),
+            SecValueType::Obj(v) => v.set_meta(meta),
+            SecValueType::List(v) => v.set_meta(meta),
+        }
+    }
+    /// 节点自身或其任意后代是否为密文。
+    pub fn contains_secret(&self) -> bool {
+        match self {
+            SecValueType::Obj(v) => v.is_secret || v.value.values().any(Self::contains_secret),
+            SecValueType::List(v) => v.is_secret || v.value.iter().any(Self::contains_secret),
+            _ => self.is_secret(),
         }
     }
 }
@@ -284,8 +465,8 @@ impl NoSecConv<ValueType> for SecValueType {
             SecValueType::Number(v) => ValueType::from(v.value),
             SecValueType::Float(v) => ValueType::from(v.value),
             SecValueType::Ip(v) => ValueType::from(v.value),
-            SecValueType::Obj(v) => ValueType::from(v.no_sec()),
-            SecValueType::List(v) => ValueType::from(v.no_sec()),
+            SecValueType::Obj(v) => ValueType::from(v.value.no_sec()),
+            SecValueType::List(v) => ValueType::from(v.value.no_sec()),
         }
     }
 }
@@ -322,7 +503,7 @@ impl ValueGetter<SecValueType> for SecValueObj {
                 let obj = current_obj.or_else(|| current_value.and_then(as_obj))?;
                 let value = obj.get(&UniString::from(key.to_string()))?;
                 let list = match value {
-                    SecValueType::List(list) => list,
+                    SecValueType::List(list) => &list.value,
                     _ => return None,
                 };
                 let item = list.get(index)?;
@@ -353,7 +534,7 @@ fn parse_index(part: &str) -> Option<(&str, usize)> {
 
 fn as_obj(value: &SecValueType) -> Option<&SecValueObj> {
     match value {
-        SecValueType::Obj(map) => Some(map),
+        SecValueType::Obj(map) => Some(&map.value),
         _ => None,
     }
 }
@@ -382,11 +563,11 @@ mod tests {
             SecValueType::nor_from(42u64),
             SecValueType::sec_from("secret".to_string()),
         ];
-        obj.insert("A".into(), SecValueType::List(list));
+        obj.insert("A".into(), SecValueType::from(list));
 
         let mut nested_obj = UniCaseMap::new();
         nested_obj.insert("rust".into(), SecValueType::nor_from("awesome".to_string()));
-        obj.insert("B".into(), SecValueType::Obj(nested_obj));
+        obj.insert("B".into(), SecValueType::from(nested_obj));
 
         // 测试数组访问
         assert_eq!(obj.value_get("A[0]"), Some(SecValueType::nor_from(42u64)));
@@ -416,10 +597,10 @@ mod tests {
         nested_c.insert("c".into(), SecValueType::nor_from("value_c".to_string()));
 
         let mut nested_b = UniCaseMap::new();
-        nested_b.insert("b".into(), SecValueType::Obj(nested_c.clone()));
+        nested_b.insert("b".into(), SecValueType::from(nested_c.clone()));
         nested_b.insert("d".into(), SecValueType::nor_from(42u64));
 
-        root.insert("a".into(), SecValueType::Obj(nested_b));
+        root.insert("a".into(), SecValueType::from(nested_b));
         root.insert("x".into(), SecValueType::nor_from("value_x".to_string()));
 
         // 测试用例
@@ -432,7 +613,7 @@ mod tests {
         // 2. 访问单层嵌套路径 "a.b"
         if let Some(SecValueType::Obj(map)) = root.value_get("a") {
             assert_eq!(
-                map.value_get("b"),
+                map.value().value_get("b"),
                 Some(SecValueType::from(nested_c)) // 实际应为嵌套的 `nested_c`
             );
         } else {
@@ -486,7 +667,8 @@ mod tests {
         obj.insert("key".to_string(), ValueType::String("value".to_string()));
 
         let secret_obj = SecValueType::sec_from(obj.clone());
-        if let SecValueType::Obj(_map) = secret_obj {
+        if let SecValueType::Obj(map) = secret_obj {
+            assert!(map.is_secret());
         } else {
             panic!("Expected Obj variant");
         }
@@ -495,7 +677,8 @@ mod tests {
         let list = vec![ValueType::Bool(true), ValueType::Number(10)];
         let public_list = SecValueType::nor_from(list.clone());
         if let SecValueType::List(vec) = public_list {
-            assert!(!vec[0].is_secret());
+            assert!(!vec.is_secret());
+            assert!(!vec.value()[0].is_secret());
         } else {
             panic!("Expected List variant");
         }
@@ -527,7 +710,7 @@ mod tests {
         // Test nested conversion
         let mut obj = UniCaseMap::new();
         obj.insert("nested".into(), SecValueType::nor_from(100u64));
-        let sec_obj = SecValueType::Obj(obj);
+        let sec_obj = SecValueType::from(obj);
 
         if let ValueType::Obj(normal_obj) = sec_obj.no_sec() {
             assert_eq!(normal_obj["NESTED"], ValueType::Number(100));
@@ -544,27 +727,93 @@ mod tests {
         obj.insert("nested".into(), SecValueType::nor_from(2u64));
         let list = vec![SecValueType::nor_from(3u64)];
         assert_eq!(
-            SecValueType::Obj(obj).partial_cmp(&SecValueType::List(list)),
+            SecValueType::from(obj).partial_cmp(&SecValueType::from(list)),
             None
         );
     }
 
-    // Helper to check if a SecValueType is secret
-    trait SecretCheck {
-        fn is_secret(&self) -> bool;
+    fn credentials() -> SecValueType {
+        let mut creds = UniCaseMap::new();
+        creds.insert("user".into(), SecValueType::nor_from("admin".to_string()));
+        creds.insert("pass".into(), SecValueType::nor_from("hunter2".to_string()));
+        SecValueType::from(creds)
     }
 
-    impl SecretCheck for SecValueType {
-        fn is_secret(&self) -> bool {
-            match self {
-                SecValueType::String(v) => v.is_secret,
-                SecValueType::Bool(v) => v.is_secret,
-                SecValueType::Number(v) => v.is_secret,
-                SecValueType::Float(v) => v.is_secret,
-                SecValueType::Ip(v) => v.is_secret,
-                SecValueType::Obj(_) => false, // Objects don't have direct secret flag
-                SecValueType::List(_) => false, // Lists don't have direct secret flag
-            }
+    #[test]
+    fn test_container_secret_flag() {
+        let public = credentials();
+        assert!(!public.is_secret());
+        assert!(!public.contains_secret());
+
+        let secret = public.clone().to_sec();
+        assert!(secret.is_secret());
+        assert!(secret.contains_secret());
+        if let SecValueType::Obj(map) = &secret {
+            assert!(map.value().values().all(SecValueType::is_secret));
+        }
+
+        let back = secret.to_nor();
+        assert!(!back.is_secret());
+        assert!(!back.contains_secret());
+    }
+
+    #[test]
+    fn test_contains_secret_nested() {
+        let mut root = UniCaseMap::new();
+        root.insert("name".into(), SecValueType::nor_from("app".to_string()));
+        root.insert(
+            "list".into(),
+            SecValueType::from(vec![
+                SecValueType::nor_from(1u64),
+                SecValueType::sec_from(2u64),
+            ]),
+        );
+        let root = SecValueType::from(root);
+        assert!(!root.is_secret());
+        assert!(root.contains_secret());
+    }
+
+    #[test]
+    fn test_secret_container_masked() {
+        let secret = credentials().to_sec();
+        assert_eq!(format!("{secret}"), SECRET_MASK);
+        let debug = format!("{secret:?}");
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("admin"));
+
+        let public = credentials();
+        assert_eq!(format!("{public}"), "obj[2]");
+        assert!(format!("{public:?}").contains("hunter2"));
+    }
+
+    #[test]
+    fn test_secret_container_serialize_as_unit() {
+        let secret = SecValueType::sec_from(credentials().no_sec());
+        let yaml = serde_yaml::to_string(&SecPlain(&secret)).unwrap();
+        let back: SecValueType = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(back, secret);
+        assert!(back.is_secret());
+    }
+
+    #[test]
+    fn test_serialize_masks_secrets_by_default() {
+        let mut obj = SecValueObj::new();
+        obj.insert("USER".into(), SecValueType::nor_from("admin".to_string()));
+        obj.insert("PASS".into(), SecValueType::sec_from("hunter2".to_string()));
+        obj.insert("PIN".into(), SecValueType::sec_from(123456u64));
+        obj.insert("CREDS".into(), credentials().to_sec());
+        let value = SecValueType::from(obj);
+        for text in [
+            serde_json::to_string(&value).unwrap(),
+            serde_yaml::to_string(&value).unwrap(),
+        ] {
+            assert!(!text.contains("hunter2"), "{text}");
+            assert!(!text.contains("123456"), "{text}");
+            assert!(text.contains("admin"), "{text}");
+            assert!(text.contains(SECRET_MASK), "{text}");
         }
+        let plain = serde_json::to_string(&SecPlain(&value)).unwrap();
+        assert!(plain.contains("hunter2") && plain.contains("123456"));
+        assert!(!serde_json::to_string(&value).unwrap().contains("hunter2"));
     }
 }
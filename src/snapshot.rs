@@ -0,0 +1,267 @@
+use std::{
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
+
+use arc_swap::ArcSwap;
+
+use crate::{error::SecResult, sec::SecValueObj};
+
+/// 某一时刻的密文快照，`version` 从 1 开始，每次替换加一。
+#[derive(Debug, PartialEq)]
+pub struct SecSnapshot {
+    version: u64,
+    obj: SecValueObj,
+}
+
+impl SecSnapshot {
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn obj(&self) -> &SecValueObj {
+        &self.obj
+    }
+}
+
+impl Deref for SecSnapshot {
+    type Target = SecValueObj;
+
+    fn deref(&self) -> &Self::Target {
+        &self.obj
+    }
+}
+
+/// 订阅编号，用于 [`SecHandle::unsubscribe`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Subscriber = Arc<dyn Fn(&Arc<SecSnapshot>) + Send + Sync>;
+
+struct Inner {
+    current: ArcSwap<SecSnapshot>,
+    // 串行化写入，保证版本号单调且通知顺序与版本一致
+    writer: Mutex<()>,
+    subscribers: Mutex<Vec<(SubscriptionId, Subscriber)>>,
+    next_id: Mutex<u64>,
+}
+
+/// 共享的密文快照句柄。
+///
+/// 克隆只增加引用计数；`load` 无锁读取当前快照，替换是原子的，
+/// 读者要么看到旧快照要么看到新快照。各子系统应持有 `SecHandle` 而不是克隆 `SecValueObj`。
+#[derive(Clone)]
+pub struct SecHandle {
+    inner: Arc<Inner>,
+}
+
+impl SecHandle {
+    pub fn new(obj: SecValueObj) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                current: ArcSwap::from_pointee(SecSnapshot { version: 1, obj }),
+                writer: Mutex::new(()),
+                subscribers: Mutex::new(Vec::new()),
+                next_id: Mutex::new(0),
+            }),
+        }
+    }
+
+    /// 当前快照。
+    pub fn load(&self) -> Arc<SecSnapshot> {
+        self.inner.current.load_full()
+    }
+
+    pub fn version(&self) -> u64 {
+        self.inner.current.load().version
+    }
+
+    /// 无条件替换为新快照并通知订阅者，返回新版本号。
+    pub fn replace(&self, obj: SecValueObj) -> u64 {
+        self.swap(|_| Ok(Some(obj)))
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| self.version())
+    }
+
+    /// 内容与当前快照不同才替换，返回新版本号；相同时返回 `None` 且不通知。
+    pub fn replace_if_changed(&self, obj: SecValueObj) -> Option<u64> {
+        self.swap(|current| Ok((*current != obj).then_some(obj)))
+            .ok()
+            .flatten()
+    }
+
+    /// 基于当前快照计算新快照并替换；闭包返回错误时保持不变。
+    ///
+    /// 更新期间其他写入会等待，因此不会丢失并发修改。
+    pub fn update<F>(&self, f: F) -> SecResult<u64>
+    where
+        F: FnOnce(&SecValueObj) -> SecResult<SecValueObj>,
+    {
+        self.swap(|current| f(current).map(Some))
+            .map(|v| v.unwrap_or_else(|| self.version()))
+    }
+
+    /// 注册回调，每次替换后以新快照调用。
+    ///
+    /// 回调在写入线程中、持有写锁时执行，因此通知顺序与版本一致；回调内不能再写入同一句柄。
+    pub fn subscribe<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&Arc<SecSnapshot>) + Send + Sync + 'static,
+    {
+        let id = {
+            let mut next = self.inner.next_id.lock().unwrap_or_else(|e| e.into_inner());
+            *next += 1;
+            SubscriptionId(*next)
+        };
+        self.inner
+            .subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((id, Arc::new(callback)));
+        id
+    }
+
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscribers = self
+            .inner
+            .subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let before = subscribers.len();
+        subscribers.retain(|(sid, _)| *sid != id);
+        subscribers.len() != before
+    }
+
+    fn swap<F>(&self, f: F) -> SecResult<Option<u64>>
+    where
+        F: FnOnce(&SecValueObj) -> SecResult<Option<SecValueObj>>,
+    {
+        let _guard = self.inner.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let current = self.inner.current.load_full();
+        let Some(obj) = f(&current.obj)? else {
+            return Ok(None);
+        };
+        let next = Arc::new(SecSnapshot {
+            version: current.version + 1,
+            obj,
+        });
+        self.inner.current.store(next.clone());
+        // 回调期间不持有订阅表的锁，允许回调内再订阅或退订
+        let subscribers: Vec<Subscriber> = self
+            .inner
+            .subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(_, s)| s.clone())
+            .collect();
+        for callback in subscribers {
+            callback(&next);
+        }
+        Ok(Some(next.version))
+    }
+}
+
+impl Default for SecHandle {
+    fn default() -> Self {
+        Self::new(SecValueObj::new())
+    }
+}
+
+impl From<SecValueObj> for SecHandle {
+    fn from(value: SecValueObj) -> Self {
+        Self::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::{SecFrom, SecValueType};
+    use crate::types::UniCaseMap;
+    use orion_error::{ToStructError, UvsFrom};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::thread;
+
+    fn obj(token: &str) -> SecValueObj {
+        let mut obj = UniCaseMap::new();
+        obj.insert("TOKEN".into(), SecValueType::sec_from(token.to_string()));
+        obj
+    }
+
+    #[test]
+    fn test_handle_replace_bumps_version_and_notifies() {
+        let handle = SecHandle::new(obj("a"));
+        let seen = Arc::new(AtomicU64::new(0));
+        let id = {
+            let seen = seen.clone();
+            handle.subscribe(move |snap| seen.store(snap.version(), Ordering::SeqCst))
+        };
+
+        let old = handle.load();
+        assert_eq!(handle.replace(obj("b")), 2);
+        assert_eq!(seen.load(Ordering::SeqCst), 2);
+        assert_eq!(old.version(), 1);
+        assert_eq!(
+            old.get("TOKEN"),
+            Some(&SecValueType::sec_from("a".to_string()))
+        );
+        assert_eq!(
+            handle.load().get("TOKEN"),
+            Some(&SecValueType::sec_from("b".to_string()))
+        );
+
+        assert_eq!(handle.replace_if_changed(obj("b")), None);
+        assert!(handle.unsubscribe(id));
+        assert_eq!(handle.replace_if_changed(obj("c")), Some(3));
+        assert_eq!(seen.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_handle_update_keeps_snapshot_on_error() {
+        let handle = SecHandle::new(obj("a"));
+        let err = handle.update(|_| Err(crate::OrionSecReason::from_validation().to_err()));
+        assert!(err.is_err());
+        assert_eq!(handle.version(), 1);
+
+        let v = handle
+            .update(|cur| {
+                let mut next = cur.clone();
+                next.insert("EXTRA".into(), SecValueType::nor_from(1u64));
+                Ok(next)
+            })
+            .unwrap();
+        assert_eq!(v, 2);
+        assert_eq!(handle.load().len(), 2);
+    }
+
+    #[test]
+    fn test_handle_concurrent_updates_are_serialized() {
+        let handle = SecHandle::default();
+        let workers: Vec<_> = (0..8)
+            .map(|i| {
+                let handle = handle.clone();
+                thread::spawn(move || {
+                    for j in 0..25 {
+                        handle
+                            .update(|cur| {
+                                let mut next = cur.clone();
+                                next.insert(
+                                    format!("K{i}_{j}").into(),
+                                    SecValueType::nor_from(1u64),
+                                );
+                                Ok(next)
+                            })
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for w in workers {
+            w.join().unwrap();
+        }
+        assert_eq!(handle.load().len(), 200);
+        assert_eq!(handle.version(), 201);
+    }
+}
@@ -0,0 +1,406 @@
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use log::info;
+use orion_error::{ErrorOwe, ErrorWith, ToStructError, UvsFrom};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    error::{OrionSecReason, SecResult},
+    fsio::{create_private_dir, lock_exclusive, write_private},
+    load::{GALAXY_DOT_DIR, dot_path},
+    sec::{SecPlain, SecValueObj, SecValueType},
+    types::UniString,
+};
+
+const SEC_VERSIONS_DIR_NAME: &str = "sec_versions";
+const LOCK_FILE_NAME: &str = ".lock";
+const HISTORY_EXT: &str = "yml";
+const DEFAULT_KEEP: usize = 5;
+
+/// 某个键的一个历史版本（不含取值）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecVersionInfo {
+    pub version: u64,
+    pub created_at: DateTime<Utc>,
+    pub is_current: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredVersion {
+    version: u64,
+    created_at: DateTime<Utc>,
+    value: SecValueType,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct KeyHistory {
+    current: u64,
+    versions: Vec<StoredVersion>,
+}
+
+impl KeyHistory {
+    fn find(&self, version: u64) -> Option<&StoredVersion> {
+        self.versions.iter().find(|v| v.version == version)
+    }
+
+    fn prune(&mut self, keep: usize) -> usize {
+        let before = self.versions.len();
+        let mut excess = before.saturating_sub(keep.max(1));
+        let current = self.current;
+        // versions 按版本号升序，优先删除最旧的非当前版本
+        self.versions.retain(|v| {
+            if excess > 0 && v.version != current {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+        before - self.versions.len()
+    }
+}
+
+/// 保存在点目录中的多版本密文仓库，每个键一个文件，保留最近 `keep` 个版本。
+///
+/// 所有写入都通过临时文件 + rename 完成，目录权限 0700、文件权限 0600；
+/// `put`/`promote`/`prune` 持有目录下 `.lock` 文件的排他锁，多个进程可安全并发修改。
+/// 键名大小写不敏感，与 `SecValueObj` 一致。
+#[derive(Debug, Clone)]
+pub struct SecVersionStore {
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl SecVersionStore {
+    /// 打开（必要时以 0700 创建）`dir` 下的仓库；已有目录对组或其他用户开放时返回错误。
+    pub fn open<P: Into<PathBuf>>(dir: P) -> SecResult<Self> {
+        let dir = dir.into();
+        create_private_dir(&dir)?;
+        Ok(Self {
+            dir,
+            keep: DEFAULT_KEEP,
+        })
+    }
+
+    /// 打开 `~/.galaxy/sec_versions`（优先当前目录下的 `.galaxy`）。
+    pub fn open_default() -> SecResult<Self> {
+        Self::open(dot_path(GALAXY_DOT_DIR).join(SEC_VERSIONS_DIR_NAME))
+    }
+
+    /// 每个键保留的版本数（含当前版本），默认 5，最少 1。
+    #[must_use]
+    pub fn with_keep(mut self, keep: usize) -> Self {
+        self.keep = keep.max(1);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 写入新版本并设为当前版本，返回新版本号；超过保留数的旧版本会被清理。
+    pub fn put<K: Into<UniString>>(&self, key: K, value: SecValueType) -> SecResult<u64> {
+        let key = key.into();
+        let _lock = self.lock()?;
+        let mut history = self.read(&key)?;
+        let version = history
+            .versions
+            .iter()
+            .map(|v| v.version)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        history.versions.push(StoredVersion {
+            version,
+            created_at: Utc::now(),
+            value,
+        });
+        history.current = version;
+        history.prune(self.keep);
+        self.write(&key, &history)?;
+        info!(target: "exec", "  store {} version {}", key.as_str(), version);
+        Ok(version)
+    }
+
+    /// 当前版本的取值。
+    pub fn current<K: Into<UniString>>(&self, key: K) -> SecResult<Option<SecValueType>> {
+        let history = self.read(&key.into())?;
+        Ok(history.find(history.current).map(|v| v.value.clone()))
+    }
+
+    pub fn get<K: Into<UniString>>(&self, key: K, version: u64) -> SecResult<Option<SecValueType>> {
+        Ok(self
+            .read(&key.into())?
+            .find(version)
+            .map(|v| v.value.clone()))
+    }
+
+    /// 按版本号升序列出历史版本。
+    pub fn versions<K: Into<UniString>>(&self, key: K) -> SecResult<Vec<SecVersionInfo>> {
+        let history = self.read(&key.into())?;
+        Ok(history
+            .versions
+            .iter()
+            .map(|v| SecVersionInfo {
+                version: v.version,
+                created_at: v.created_at,
+                is_current: v.version == history.current,
+            })
+            .collect())
+    }
+
+    /// 将已有的历史版本设为当前版本（回滚）；版本不存在时返回错误。
+    pub fn promote<K: Into<UniString>>(&self, key: K, version: u64) -> SecResult<()> {
+        let key = key.into();
+        let _lock = self.lock()?;
+        let mut history = self.read(&key)?;
+        if history.find(version).is_none() {
+            return OrionSecReason::from_validation()
+                .to_err()
+                .with_detail(format!("{} has no version {version}", key.as_str()))
+                .err();
+        }
+        history.current = version;
+        self.write(&key, &history)?;
+        info!(target: "exec", "  promote {} to version {}", key.as_str(), version);
+        Ok(())
+    }
+
+    /// 只保留最近 `keep` 个版本（当前版本总会保留），返回删除的个数。
+    pub fn prune<K: Into<UniString>>(&self, key: K, keep: usize) -> SecResult<usize> {
+        let key = key.into();
+        let _lock = self.lock()?;
+        let mut history = self.read(&key)?;
+        let removed = history.prune(keep);
+        if removed > 0 {
+            self.write(&key, &history)?;
+        }
+        Ok(removed)
+    }
+
+    /// 仓库中全部键，按名称排序。
+    pub fn keys(&self) -> SecResult<Vec<UniString>> {
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.dir).owe_sys().with(&self.dir)? {
+            let path = entry.owe_sys().with(&self.dir)?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(HISTORY_EXT) {
+                continue;
+            }
+            if let Some(key) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(decode_key)
+            {
+                keys.push(UniString::from(key));
+            }
+        }
+        keys.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        Ok(keys)
+    }
+
+    /// 以各键当前版本组成的 `SecValueObj`。
+    pub fn snapshot(&self) -> SecResult<SecValueObj> {
+        let mut obj = SecValueObj::new();
+        for key in self.keys()? {
+            if let Some(value) = self.current(key.clone())? {
+                obj.insert(key, value);
+            }
+        }
+        Ok(obj)
+    }
+
+    fn lock(&self) -> SecResult<File> {
+        lock_exclusive(&self.dir.join(LOCK_FILE_NAME))
+    }
+
+    fn path_of(&self, key: &UniString) -> PathBuf {
+        self.dir
+            .join(format!("{}.{HISTORY_EXT}", encode_key(key.as_str())))
+    }
+
+    fn read(&self, key: &UniString) -> SecResult<KeyHistory> {
+        let path = self.path_of(key);
+        if !path.exists() {
+            return Ok(KeyHistory::default());
+        }
+        let content = fs::read_to_string(&path).owe_sys().with(&path)?;
+        serde_yaml::from_str(&content).owe_data().with(&path)
+    }
+
+    fn write(&self, key: &UniString, history: &KeyHistory) -> SecResult<()> {
+        let path = self.path_of(key);
+        let content = serde_yaml::to_string(&SecPlain(history))
+            .owe_data()
+            .with(&path)?;
+        write_private(&path, content.as_bytes())
+    }
+}
+
+/// 键名转为文件名：字母数字与 `_`、`-` 原样保留，其余字节写作 `%XX`。
+fn encode_key(key: &str) -> String {
+    key.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn decode_key(name: &str) -> Option<String> {
+    let bytes = name.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = name.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::SecFrom;
+    use tempfile::TempDir;
+
+    fn sec(v: &str) -> SecValueType {
+        SecValueType::sec_from(v.to_string())
+    }
+
+    #[test]
+    fn test_store_put_get_and_list() {
+        let dir = TempDir::new().unwrap();
+        let store = SecVersionStore::open(dir.path().join("versions")).unwrap();
+        assert_eq!(store.put("sec_db_pass", sec("a")).unwrap(), 1);
+        assert_eq!(store.put("SEC_DB_PASS", sec("b")).unwrap(), 2);
+
+        assert_eq!(store.current("sec_db_pass").unwrap(), Some(sec("b")));
+        assert_eq!(store.get("SEC_DB_PASS", 1).unwrap(), Some(sec("a")));
+        assert_eq!(store.get("SEC_DB_PASS", 9).unwrap(), None);
+        let versions = store.versions("SEC_DB_PASS").unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(versions[1].is_current && !versions[0].is_current);
+        assert!(store.versions("missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_store_promote_and_keep() {
+        let dir = TempDir::new().unwrap();
+        let store = SecVersionStore::open(dir.path().join("versions"))
+            .unwrap()
+            .with_keep(2);
+        for v in ["a", "b", "c"] {
+            store.put("TOKEN", sec(v)).unwrap();
+        }
+        let kept: Vec<u64> = store
+            .versions("TOKEN")
+            .unwrap()
+            .iter()
+            .map(|v| v.version)
+            .collect();
+        assert_eq!(kept, vec![2, 3]);
+
+        store.promote("TOKEN", 2).unwrap();
+        assert_eq!(store.current("TOKEN").unwrap(), Some(sec("b")));
+        assert!(store.promote("TOKEN", 1).is_err());
+
+        // 新版本在最大版本号之后，回滚过的当前版本在清理时保留
+        assert_eq!(store.put("TOKEN", sec("d")).unwrap(), 4);
+        store.promote("TOKEN", 3).unwrap();
+        assert_eq!(store.prune("TOKEN", 1).unwrap(), 1);
+        let kept: Vec<u64> = store
+            .versions("TOKEN")
+            .unwrap()
+            .iter()
+            .map(|v| v.version)
+            .collect();
+        assert_eq!(kept, vec![3]);
+    }
+
+    #[test]
+    fn test_store_keys_and_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let store = SecVersionStore::open(dir.path().join("versions")).unwrap();
+        store.put("SEC_A", sec("1")).unwrap();
+        store.put("odd key/x", sec("2")).unwrap();
+        let keys: Vec<String> = store
+            .keys()
+            .unwrap()
+            .iter()
+            .map(|k| k.as_str().to_string())
+            .collect();
+        assert_eq!(keys, vec!["ODD KEY/X", "SEC_A"]);
+        let snapshot = store.snapshot().unwrap();
+        assert_eq!(snapshot.get("ODD KEY/X"), Some(&sec("2")));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(store.dir()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+    }
+
+    #[test]
+    fn test_store_concurrent_puts_keep_every_version() {
+        let dir = TempDir::new().unwrap();
+        SecVersionStore::open(dir.path().join("versions")).unwrap();
+        std::thread::scope(|s| {
+            for t in 0..8 {
+                let path = dir.path().join("versions");
+                s.spawn(move || {
+                    let store = SecVersionStore::open(path).unwrap().with_keep(100);
+                    for i in 0..10 {
+                        store.put("TOKEN", sec(&format!("{t}-{i}"))).unwrap();
+                    }
+                });
+            }
+        });
+        let store = SecVersionStore::open(dir.path().join("versions")).unwrap();
+        let versions: Vec<u64> = store
+            .versions("TOKEN")
+            .unwrap()
+            .iter()
+            .map(|v| v.version)
+            .collect();
+        assert_eq!(versions, (1..=80).collect::<Vec<_>>());
+        assert_eq!(store.keys().unwrap().len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_store_open_keeps_existing_dir_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("shared");
+        fs::create_dir(&path).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(SecVersionStore::open(&path).is_err());
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o500)).unwrap();
+        SecVersionStore::open(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o500);
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o700)).unwrap();
+
+        // 新建的各级目录在创建时即为 0700
+        let nested = dir.path().join("a").join("b");
+        SecVersionStore::open(&nested).unwrap();
+        for created in [dir.path().join("a"), nested] {
+            let mode = fs::metadata(&created).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use indexmap::IndexMap;
+
+use crate::{
+    query::{PathSeg, SecPath},
+    sec::{SecList, SecObj, SecValueObj, SecValueType},
+    types::UniString,
+};
+
+/// 只读遍历 `SecValueType` 树，回调中携带当前节点的路径。
+///
+/// 所有方法都有空的默认实现，只需覆盖关心的节点类型。
+/// `enter_*` 返回 `false` 时跳过该容器的子节点（对应的 `leave_*` 仍会被调用）。
+pub trait SecVisitor {
+    fn visit_leaf(&mut self, _path: &SecPath, _value: &SecValueType) {}
+    fn enter_obj(&mut self, _path: &SecPath, _obj: &SecObj) -> bool {
+        true
+    }
+    fn leave_obj(&mut self, _path: &SecPath, _obj: &SecObj) {}
+    fn enter_list(&mut self, _path: &SecPath, _list: &SecList) -> bool {
+        true
+    }
+    fn leave_list(&mut self, _path: &SecPath, _list: &SecList) {}
+}
+
+/// 可修改节点的遍历，语义同 [`SecVisitor`]。
+///
+/// 子节点在 `enter_*_mut` 之后遍历，因此可以在进入容器时整体替换其内容。
+pub trait SecVisitorMut {
+    fn visit_leaf_mut(&mut self, _path: &SecPath, _value: &mut SecValueType) {}
+    fn enter_obj_mut(&mut self, _path: &SecPath, _obj: &mut SecObj) -> bool {
+        true
+    }
+    fn leave_obj_mut(&mut self, _path: &SecPath, _obj: &mut SecObj) {}
+    fn enter_list_mut(&mut self, _path: &SecPath, _list: &mut SecList) -> bool {
+        true
+    }
+    fn leave_list_mut(&mut self, _path: &SecPath, _list: &mut SecList) {}
+}
+
+/// 自底向上归约 `SecValueType` 树：子节点的结果先算出，再交给容器合并。
+pub trait SecFold {
+    type Output;
+    fn fold_leaf(&mut self, path: &SecPath, value: &SecValueType) -> Self::Output;
+    fn fold_obj(
+        &mut self,
+        path: &SecPath,
+        obj: &SecObj,
+        children: IndexMap<UniString, Self::Output>,
+    ) -> Self::Output;
+    fn fold_list(
+        &mut self,
+        path: &SecPath,
+        list: &SecList,
+        items: Vec<Self::Output>,
+    ) -> Self::Output;
+}
+
+pub fn visit<V: SecVisitor + ?Sized>(obj: &SecValueObj, visitor: &mut V) {
+    let mut path = SecPath::new();
+    visit_children(&mut path, obj, visitor);
+}
+
+pub fn visit_value<V: SecVisitor + ?Sized>(value: &SecValueType, visitor: &mut V) {
+    visit_node(&mut SecPath::new(), value, visitor);
+}
+
+pub fn visit_mut<V: SecVisitorMut + ?Sized>(obj: &mut SecValueObj, visitor: &mut V) {
+    let mut path = SecPath::new();
+    visit_children_mut(&mut path, obj, visitor);
+}
+
+pub fn visit_value_mut<V: SecVisitorMut + ?Sized>(value: &mut SecValueType, visitor: &mut V) {
+    visit_node_mut(&mut SecPath::new(), value, visitor);
+}
+
+/// 对根对象的每个顶层条目分别归约。
+pub fn fold<F: SecFold + ?Sized>(
+    obj: &SecValueObj,
+    folder: &mut F,
+) -> IndexMap<UniString, F::Output> {
+    let mut path = SecPath::new();
+    fold_children(&mut path, obj, folder)
+}
+
+pub fn fold_value<F: SecFold + ?Sized>(value: &SecValueType, folder: &mut F) -> F::Output {
+    fold_node(&mut SecPath::new(), value, folder)
+}
+
+fn visit_children<V: SecVisitor + ?Sized>(path: &mut SecPath, obj: &SecValueObj, visitor: &mut V) {
+    for (k, v) in obj.iter() {
+        path.push(PathSeg::Key(k.clone()));
+        visit_node(path, v, visitor);
+        path.pop();
+    }
+}
+
+fn visit_node<V: SecVisitor + ?Sized>(path: &mut SecPath, value: &SecValueType, visitor: &mut V) {
+    match value {
+        SecValueType::Obj(obj) => {
+            if visitor.enter_obj(path, obj) {
+                visit_children(path, obj.value(), visitor);
+            }
+            visitor.leave_obj(path, obj);
+        }
+        SecValueType::List(list) => {
+            if visitor.enter_list(path, list) {
+                for (i, v) in list.value().iter().enumerate() {
+                    path.push(PathSeg::Index(i));
+                    visit_node(path, v, visitor);
+                    path.pop();
+                }
+            }
+            visitor.leave_list(path, list);
+        }
+        leaf => visitor.visit_leaf(path, leaf),
+    }
+}
+
+fn visit_children_mut<V: SecVisitorMut + ?Sized>(
+    path: &mut SecPath,
+    obj: &mut SecValueObj,
+    visitor: &mut V,
+) {
+    for (k, v) in obj.iter_mut() {
+        path.push(PathSeg::Key(k.clone()));
+        visit_node_mut(path, v, visitor);
+        path.pop();
+    }
+}
+
+fn visit_node_mut<V: SecVisitorMut + ?Sized>(
+    path: &mut SecPath,
+    value: &mut SecValueType,
+    visitor: &mut V,
+) {
+    match value {
+        SecValueType::Obj(obj) => {
+            if visitor.enter_obj_mut(path, obj) {
+                visit_children_mut(path, obj.value_mut(), visitor);
+            }
+            visitor.leave_obj_mut(path, obj);
+        }
+        SecValueType::List(list) => {
+            if visitor.enter_list_mut(path, list) {
+                for (i, v) in list.value_mut().iter_mut().enumerate() {
+                    path.push(PathSeg::Index(i));
+                    visit_node_mut(path, v, visitor);
+                    path.pop();
+                }
+            }
+            visitor.leave_list_mut(path, list);
+        }
+        leaf => visitor.visit_leaf_mut(path, leaf),
+    }
+}
+
+fn fold_children<F: SecFold + ?Sized>(
+    path: &mut SecPath,
+    obj: &SecValueObj,
+    folder: &mut F,
+) -> IndexMap<UniString, F::Output> {
+    obj.iter()
+        .map(|(k, v)| {
+            path.push(PathSeg::Key(k.clone()));
+            let out = fold_node(path, v, folder);
+            path.pop();
+            (k.clone(), out)
+        })
+        .collect()
+}
+
+fn fold_node<F: SecFold + ?Sized>(
+    path: &mut SecPath,
+    value: &SecValueType,
+    folder: &mut F,
+) -> F::Output {
+    match value {
+        SecValueType::Obj(obj) => {
+            let children = fold_children(path, obj.value(), folder);
+            folder.fold_obj(path, obj, children)
+        }
+        SecValueType::List(list) => {
+            let items = list
+                .value()
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    path.push(PathSeg::Index(i));
+                    let out = fold_node(path, v, folder);
+                    path.pop();
+                    out
+                })
+                .collect();
+            folder.fold_list(path, list, items)
+        }
+        leaf => folder.fold_leaf(path, leaf),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::replace_with;
+    use crate::sec::{SecFrom, ValueGetter};
+    use crate::types::UniCaseMap;
+
+    fn sample() -> SecValueObj {
+        let mut db = UniCaseMap::new();
+        db.insert("user".into(), SecValueType::nor_from("root".to_string()));
+        db.insert("pass".into(), SecValueType::sec_from("p".to_string()));
+        let mut root = UniCaseMap::new();
+        root.insert("db".into(), SecValueType::from(db));
+        root.insert(
+            "ports".into(),
+            SecValueType::from(vec![
+                SecValueType::nor_from(80u64),
+                SecValueType::nor_from(443u64),
+            ]),
+        );
+        root
+    }
+
+    #[derive(Default)]
+    struct Collect {
+        leaves: Vec<String>,
+        containers: Vec<String>,
+    }
+
+    impl SecVisitor for Collect {
+        fn visit_leaf(&mut self, path: &SecPath, _value: &SecValueType) {
+            self.leaves.push(path.to_string());
+        }
+        fn enter_obj(&mut self, path: &SecPath, _obj: &SecObj) -> bool {
+            self.containers.push(path.to_string());
+            true
+        }
+        fn enter_list(&mut self, path: &SecPath, _list: &SecList) -> bool {
+            self.containers.push(path.to_string());
+            false
+        }
+    }
+
+    #[test]
+    fn test_visit_tracks_paths() {
+        let mut collect = Collect::default();
+        visit(&sample(), &mut collect);
+        assert_eq!(collect.leaves, vec!["DB.USER", "DB.PASS"]);
+        assert_eq!(collect.containers, vec!["DB", "PORTS"]);
+    }
+
+    struct MaskSecrets;
+
+    impl SecVisitorMut for MaskSecrets {
+        fn visit_leaf_mut(&mut self, _path: &SecPath, value: &mut SecValueType) {
+            if value.is_secret() {
+                *value = SecValueType::sec_from("***".to_string());
+            }
+        }
+    }
+
+    struct Publish;
+
+    impl SecVisitorMut for Publish {
+        fn enter_obj_mut(&mut self, _path: &SecPath, obj: &mut SecObj) -> bool {
+            obj.set_secret(false);
+            true
+        }
+        fn visit_leaf_mut(&mut self, _path: &SecPath, value: &mut SecValueType) {
+            replace_with(value, SecValueType::to_nor);
+        }
+    }
+
+    #[test]
+    fn test_visit_mut_transforms_leaves() {
+        let mut obj = sample();
+        visit_mut(&mut obj, &mut MaskSecrets);
+        assert_eq!(
+            obj.value_get("db.pass"),
+            Some(SecValueType::sec_from("***".to_string()))
+        );
+        assert_eq!(
+            obj.value_get("db.user"),
+            Some(SecValueType::nor_from("root".to_string()))
+        );
+
+        visit_mut(&mut obj, &mut Publish);
+        assert!(!obj.values().any(SecValueType::contains_secret));
+    }
+
+    struct CountSecrets;
+
+    impl SecFold for CountSecrets {
+        type Output = usize;
+        fn fold_leaf(&mut self, _path: &SecPath, value: &SecValueType) -> usize {
+            usize::from(value.is_secret())
+        }
+        fn fold_obj(
+            &mut self,
+            _path: &SecPath,
+            _obj: &SecObj,
+            children: IndexMap<UniString, usize>,
+        ) -> usize {
+            children.values().sum()
+        }
+        fn fold_list(&mut self, _path: &SecPath, _list: &SecList, items: Vec<usize>) -> usize {
+            items.into_iter().sum()
+        }
+    }
+
+    #[test]
+    fn test_fold_counts_secrets() {
+        let obj = sample();
+        let counts = fold(&obj, &mut CountSecrets);
+        assert_eq!(counts[&UniString::from("db")], 1);
+        assert_eq!(counts[&UniString::from("ports")], 0);
+
+        let all = fold_value(&SecValueType::from(obj).to_sec(), &mut CountSecrets);
+        assert_eq!(all, 4);
+    }
+}
@@ -0,0 +1,475 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, RecvTimeoutError, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use log::{info, warn};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use orion_error::{ErrorOwe, ErrorWith, ToStructError, UvsFrom};
+
+use crate::{
+    error::{OrionSecReason, SecResult},
+    load::{SecFileFmt, SecLoadOptions, load_secfile_with},
+    sec::SecValueObj,
+    snapshot::{SecHandle, SecSnapshot, SubscriptionId},
+};
+
+type Validator = Arc<dyn Fn(&SecValueObj) -> SecResult<()> + Send + Sync>;
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_SETTLE: Duration = Duration::from_millis(100);
+
+/// 监视密文文件（或 [`SecFileFmt::Dir`] 目录），变化时重新加载并发布新的快照。
+///
+/// Linux 上通过 inotify（`notify` crate）监听所在目录，以覆盖原子替换与 `..data` 切换；
+/// 无法建立监听时退化为按 `poll_interval` 轮询。无论哪种方式都会定期轮询兜底。
+///
+/// 检测到变化后会等待 `settle` 时长，文件在这段时间内仍在变化则视为写入未完成、推迟到下一轮。
+/// 新内容解析失败、未通过校验，或文件缺失、内容为空时保留上一份快照，只记录警告。
+pub struct SecWatcher {
+    path: PathBuf,
+    fmt: SecFileFmt,
+    opts: SecLoadOptions,
+    poll_interval: Duration,
+    settle: Duration,
+    validator: Option<Validator>,
+}
+
+impl SecWatcher {
+    pub fn new<P: Into<PathBuf>>(path: P, fmt: SecFileFmt) -> Self {
+        Self {
+            path: path.into(),
+            fmt,
+            opts: SecLoadOptions::default(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            settle: DEFAULT_SETTLE,
+            validator: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_options(mut self, opts: SecLoadOptions) -> Self {
+        self.opts = opts;
+        self
+    }
+
+    #[must_use]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// 判定写入完成所需的静默时长，默认 100ms。
+    #[must_use]
+    pub fn with_settle(mut self, settle: Duration) -> Self {
+        self.settle = settle;
+        self
+    }
+
+    /// 发布前对新快照执行的校验，例如 `SecPolicy::check_obj`。
+    #[must_use]
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&SecValueObj) -> SecResult<()> + Send + Sync + 'static,
+    {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// 完成首次加载并启动后台监视线程；首次加载或校验失败时直接返回错误。
+    pub fn start(self) -> SecResult<SecWatchHandle> {
+        self.spawn(None)
+    }
+
+    /// 与 [`start`](Self::start) 相同，但把快照发布到已有的 [`SecHandle`]。
+    pub fn start_with(self, handle: SecHandle) -> SecResult<SecWatchHandle> {
+        self.spawn(Some(handle))
+    }
+
+    fn spawn(self, handle: Option<SecHandle>) -> SecResult<SecWatchHandle> {
+        let source = Arc::new(Source {
+            path: self.path,
+            fmt: self.fmt,
+            opts: self.opts,
+            validator: self.validator,
+        });
+        let first = source.load()?;
+        let handle = match handle {
+            Some(handle) => {
+                handle.replace_if_changed(first);
+                handle
+            }
+            None => SecHandle::new(first),
+        };
+        let shared = Arc::new(Shared {
+            handle,
+            stamp: Mutex::new(stamp(&source.path)),
+        });
+
+        let (tx, rx) = mpsc::channel();
+        let fs_watcher = fs_watcher(&source.path, source.fmt, tx.clone());
+        let worker = {
+            let source = source.clone();
+            let shared = shared.clone();
+            let poll = self.poll_interval;
+            let settle = self.settle;
+            thread::spawn(move || {
+                loop {
+                    match rx.recv_timeout(poll) {
+                        Ok(Signal::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+                        Ok(Signal::Changed) | Err(RecvTimeoutError::Timeout) => {}
+                    }
+                    // 合并同一批事件
+                    while let Ok(signal) = rx.try_recv() {
+                        if matches!(signal, Signal::Stop) {
+                            return;
+                        }
+                    }
+                    shared.check(&source, settle);
+                }
+            })
+        };
+        info!(target: "exec", "  watch {}", source.path.display());
+        Ok(SecWatchHandle {
+            source,
+            shared,
+            control: tx,
+            worker: Some(worker),
+            _fs_watcher: fs_watcher,
+        })
+    }
+}
+
+/// 正在运行的监视器；drop 时停止后台线程。
+pub struct SecWatchHandle {
+    source: Arc<Source>,
+    shared: Arc<Shared>,
+    control: Sender<Signal>,
+    worker: Option<JoinHandle<()>>,
+    _fs_watcher: Option<RecommendedWatcher>,
+}
+
+impl SecWatchHandle {
+    /// 发布快照的共享句柄，可克隆后分发给各子系统。
+    pub fn handle(&self) -> &SecHandle {
+        &self.shared.handle
+    }
+
+    /// 当前生效的快照。
+    pub fn current(&self) -> Arc<SecSnapshot> {
+        self.shared.handle.load()
+    }
+
+    /// 注册回调，每次发布新快照后调用；回调在监视线程中执行，应尽快返回。
+    pub fn subscribe<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&Arc<SecSnapshot>) + Send + Sync + 'static,
+    {
+        self.shared.handle.subscribe(callback)
+    }
+
+    /// 立即重新加载。返回是否发布了新快照；失败时保留原快照并返回错误。
+    pub fn reload(&self) -> SecResult<bool> {
+        *self.shared.stamp.lock().unwrap_or_else(|e| e.into_inner()) = stamp(&self.source.path);
+        let next = self.source.reload()?;
+        Ok(self.shared.handle.replace_if_changed(next).is_some())
+    }
+
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let _ = self.control.send(Signal::Stop);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for SecWatchHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+enum Signal {
+    Changed,
+    Stop,
+}
+
+struct Source {
+    path: PathBuf,
+    fmt: SecFileFmt,
+    opts: SecLoadOptions,
+    validator: Option<Validator>,
+}
+
+impl Source {
+    fn load(&self) -> SecResult<SecValueObj> {
+        let obj = load_secfile_with(self.path.clone(), self.fmt, &self.opts)?;
+        if let Some(validator) = &self.validator {
+            validator(&obj)?;
+        }
+        Ok(obj)
+    }
+
+    /// 重新加载时文件缺失或内容为空多半是替换、轮换过程中的中间状态，按失败处理以保留原快照。
+    fn reload(&self) -> SecResult<SecValueObj> {
+        let obj = self.load()?;
+        if obj.is_empty() {
+            return OrionSecReason::from_validation()
+                .to_err()
+                .with_detail(format!("{} is missing or empty", self.path.display()))
+                .err();
+        }
+        Ok(obj)
+    }
+}
+
+struct Shared {
+    handle: SecHandle,
+    stamp: Mutex<Stamp>,
+}
+
+impl Shared {
+    fn check(&self, source: &Source, settle: Duration) {
+        let before = stamp(&source.path);
+        if *self.stamp.lock().unwrap_or_else(|e| e.into_inner()) == before {
+            return;
+        }
+        thread::sleep(settle);
+        if stamp(&source.path) != before {
+            // 仍在写入，等下一轮
+            return;
+        }
+        *self.stamp.lock().unwrap_or_else(|e| e.into_inner()) = before;
+        match source.reload() {
+            Ok(next) => {
+                if let Some(version) = self.handle.replace_if_changed(next) {
+                    info!(target: "exec", "  reload {} as version {}", source.path.display(), version);
+                }
+            }
+            Err(err) => {
+                warn!(target: "exec", "  keep previous secrets, reload {} failed: {}", source.path.display(), err);
+            }
+        }
+    }
+}
+
+/// 文件（或目录下各文件）的长度与修改时间，用于判断是否变化以及写入是否稳定。
+type Stamp = Vec<(PathBuf, u64, Option<SystemTime>)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let entry = |p: PathBuf| {
+        fs::metadata(&p)
+            .ok()
+            .filter(|m| m.is_file())
+            .map(|m| (p, m.len(), m.modified().ok()))
+    };
+    if path.is_dir() {
+        let mut items: Stamp = fs::read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+            .filter_map(|e| entry(e.path()))
+            .collect();
+        items.sort();
+        items
+    } else {
+        entry(path.to_path_buf()).into_iter().collect()
+    }
+}
+
+fn fs_watcher(path: &Path, fmt: SecFileFmt, tx: Sender<Signal>) -> Option<RecommendedWatcher> {
+    let target = match fmt {
+        SecFileFmt::Dir => path.to_path_buf(),
+        _ => path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf(),
+    };
+    let result: SecResult<RecommendedWatcher> =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if event.is_ok() {
+                let _ = tx.send(Signal::Changed);
+            }
+        })
+        .owe_sys()
+        .and_then(|mut watcher| {
+            watcher
+                .watch(&target, RecursiveMode::NonRecursive)
+                .owe_sys()
+                .with(&target)
+                .map(|_| watcher)
+        });
+    match result {
+        Ok(watcher) => Some(watcher),
+        Err(err) => {
+            warn!(target: "exec", "  file events unavailable, polling only: {}", err);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sec::{SecFrom, SecValueType};
+    use std::time::Instant;
+    use tempfile::TempDir;
+
+    fn write_atomic(path: &Path, content: &str) {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, content).unwrap();
+        fs::rename(&tmp, path).unwrap();
+    }
+
+    fn wait_for<F: Fn() -> bool>(cond: F) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if cond() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    fn token(handle: &SecWatchHandle) -> Option<SecValueType> {
+        handle.current().get("SEC_TOKEN").cloned()
+    }
+
+    #[test]
+    fn test_watch_publishes_new_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("sec_value.yml");
+        fs::write(&file, "token: a\n").unwrap();
+
+        let handle = SecWatcher::new(&file, SecFileFmt::Yaml)
+            .with_poll_interval(Duration::from_millis(50))
+            .with_settle(Duration::from_millis(10))
+            .start()
+            .unwrap();
+        let (tx, rx) = mpsc::channel();
+        handle.subscribe(move |obj| {
+            let _ = tx.send(obj.get("SEC_TOKEN").cloned());
+        });
+
+        write_atomic(&file, "token: b\n");
+        let seen = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(seen, Some(SecValueType::sec_from("b".to_string())));
+        assert_eq!(token(&handle), seen);
+        handle.stop();
+    }
+
+    #[test]
+    fn test_watch_keeps_previous_on_parse_error() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("sec_value.yml");
+        fs::write(&file, "token: a\n").unwrap();
+        let handle = SecWatcher::new(&file, SecFileFmt::Yaml)
+            .with_poll_interval(Duration::from_millis(50))
+            .with_settle(Duration::from_millis(10))
+            .start()
+            .unwrap();
+
+        write_atomic(&file, "token: [unclosed\n");
+        assert!(handle.reload().is_err());
+        assert_eq!(
+            token(&handle),
+            Some(SecValueType::sec_from("a".to_string()))
+        );
+
+        write_atomic(&file, "token: c\n");
+        assert!(wait_for(
+            || token(&handle) == Some(SecValueType::sec_from("c".to_string()))
+        ));
+    }
+
+    #[test]
+    fn test_watch_keeps_previous_when_file_missing_or_empty() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("sec_value.yml");
+        fs::write(&file, "token: a\n").unwrap();
+        let handle = SecWatcher::new(&file, SecFileFmt::Yaml)
+            .with_poll_interval(Duration::from_millis(20))
+            .with_settle(Duration::from_millis(5))
+            .start()
+            .unwrap();
+        let a = Some(SecValueType::sec_from("a".to_string()));
+
+        fs::remove_file(&file).unwrap();
+        assert!(handle.reload().is_err());
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(token(&handle), a);
+
+        fs::write(&file, "").unwrap();
+        assert!(handle.reload().is_err());
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(token(&handle), a);
+
+        write_atomic(&file, "token: b\n");
+        assert!(wait_for(
+            || token(&handle) == Some(SecValueType::sec_from("b".to_string()))
+        ));
+    }
+
+    #[test]
+    fn test_watch_validator_rejects_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("sec_value.yml");
+        fs::write(&file, "token: a\n").unwrap();
+        let handle = SecWatcher::new(&file, SecFileFmt::Yaml)
+            .with_poll_interval(Duration::from_secs(60))
+            .with_validator(|obj| {
+                if obj.contains_key("SEC_TOKEN") {
+                    Ok(())
+                } else {
+                    Err(OrionSecReason::from_validation().to_err())
+                }
+            })
+            .start()
+            .unwrap();
+
+        fs::write(&file, "other: x\n").unwrap();
+        assert!(handle.reload().is_err());
+        assert_eq!(
+            token(&handle),
+            Some(SecValueType::sec_from("a".to_string()))
+        );
+
+        fs::write(&file, "token: a\n").unwrap();
+        assert!(!handle.reload().unwrap());
+    }
+
+    #[test]
+    fn test_watch_publishes_into_shared_handle() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("sec_value.yml");
+        fs::write(&file, "token: a\n").unwrap();
+        let shared = SecHandle::default();
+        let reader = shared.clone();
+        let handle = SecWatcher::new(&file, SecFileFmt::Yaml)
+            .with_poll_interval(Duration::from_secs(60))
+            .start_with(shared)
+            .unwrap();
+        assert_eq!(reader.version(), 2);
+
+        fs::write(&file, "token: b\n").unwrap();
+        assert!(handle.reload().unwrap());
+        assert_eq!(reader.version(), 3);
+        assert_eq!(
+            reader.load().get("SEC_TOKEN"),
+            Some(&SecValueType::sec_from("b".to_string()))
+        );
+    }
+}